@@ -0,0 +1,575 @@
+extern crate eight_queens_rs;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process;
+
+use eight_queens_rs::checker::{analyze_board, check_board, AttackDirection, CheckResult};
+use eight_queens_rs::permutation::Permutation;
+use eight_queens_rs::position_types::{CoordList, PosCoords};
+use eight_queens_rs::render::{AxisLabels, BoardRenderer, SvgRenderer};
+use eight_queens_rs::solver::{count_solutions, SearchMode, SolutionSet, Solver};
+use eight_queens_rs::Board;
+
+/// Exit code used when the board is valid, or the command succeeded.
+const EXIT_OK: i32 = 0;
+/// Exit code used when the board contains conflicting queens.
+const EXIT_CONFLICT: i32 = 1;
+/// Exit code used when the board could not be read or parsed.
+const EXIT_PARSE_ERROR: i32 = 2;
+/// Exit code used when the command line arguments are invalid.
+const EXIT_USAGE: i32 = 3;
+/// Exit code used when the output could not be written.
+const EXIT_IO_ERROR: i32 = 4;
+
+/// The number of boards in each row of an SVG solution gallery.
+const GALLERY_COLUMNS: usize = 4;
+
+static USAGE: &str = "\
+Usage: eight-queens <COMMAND> [OPTIONS]
+
+Commands:
+  solve     Print the solutions for a board of the given size
+  count     Print the number of solutions for a board of the given size
+  check     Read a board, and print the result of checking it
+  render    Read a board, and print it using the given rendering options
+  help      Print this message
+
+Board size options (solve, count):
+  -n, --size <N>        Use an N x N board (default: 8)
+  --width <W>           Set the width of the board
+  --height <H>          Set the height of the board
+
+Solve options:
+  --mode <MODE>         heuristic, exhaustive, or bitboard (default: bitboard)
+  --format <FORMAT>     board, coords, perm, or svg (default: board)
+  --limit <K>           Print the first K solutions found, in the order they
+                        are found, rather than every solution in sorted order
+
+Count options:
+  --threads <T>         Split the search across T threads (default: 1)
+
+Check and render options:
+  [FILE]                Read the board from FILE, or from stdin if omitted or '-'
+  -q, --quiet           (check) Only report the result using the exit code,
+                        rather than printing the result and each attacking pair
+  --labels <LABELS>     (render) none, numeric, or chess (default: none)
+  --contested           (render) Mark the squares contested by a queen
+  --conflicts           (render) Highlight queens that are in conflict
+  --unicode             (render) Use Unicode chess glyphs
+  --checkerboard        (render) Shade the dark squares
+  --svg                 (render) Print an SVG image, using --conflicts and --rays
+  --rays                (render) Draw the attack rays of each queen in SVG images
+
+Boards are read in the format printed by `render`, or without a border
+using '.' for empty squares and 'Q' for queens.
+
+Exit codes:
+  0  success, or the board has no conflicts
+  1  the board has conflicting queens
+  2  the board could not be read or parsed
+  3  invalid command line arguments
+  4  the output could not be written";
+
+/// The formats used to print solutions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Board,
+    Coords,
+    Permutation,
+    Svg,
+}
+
+/// The source of a board that should be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Input {
+    Stdin,
+    File(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Command {
+    Solve {
+        dims: PosCoords,
+        mode: SearchMode,
+        format: OutputFormat,
+        limit: Option<usize>,
+    },
+    Count {
+        dims: PosCoords,
+        threads: usize,
+    },
+    Check {
+        input: Input,
+        quiet: bool,
+    },
+    Render {
+        input: Input,
+        renderer: BoardRenderer,
+        svg: Option<SvgRenderer>,
+    },
+    Help,
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let stdout = io::stdout();
+    let code = match parse_args(&args) {
+        Ok(command) => run(command, &mut stdout.lock()),
+        Err(msg) => {
+            eprintln!("error: {}\n\n{}", msg, USAGE);
+            EXIT_USAGE
+        }
+    };
+    process::exit(code);
+}
+
+/// Run a command, writing its output, and return the exit code.
+fn run<W: Write>(command: Command, out: &mut W) -> i32 {
+    let res = match command {
+        Command::Solve {
+            dims,
+            mode,
+            format,
+            limit,
+        } => solve(dims, mode, format, limit, out).map(|_| EXIT_OK),
+        Command::Count { dims, threads } => count(dims, threads, out).map(|_| EXIT_OK),
+        Command::Check { input, quiet } => match read_board(&input) {
+            Ok(board) => check(&board, quiet, out),
+            Err(msg) => return report_parse_error(&msg),
+        },
+        Command::Render {
+            input,
+            renderer,
+            svg,
+        } => match read_board(&input) {
+            Ok(board) => match svg {
+                Some(svg) => write!(out, "{}", svg.render(&board)),
+                None => writeln!(out, "{}", renderer.render(&board)),
+            }
+            .map(|_| EXIT_OK),
+            Err(msg) => return report_parse_error(&msg),
+        },
+        Command::Help => writeln!(out, "{}", USAGE).map(|_| EXIT_OK),
+    };
+    res.unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        EXIT_IO_ERROR
+    })
+}
+
+fn report_parse_error(msg: &str) -> i32 {
+    eprintln!("error: {}", msg);
+    EXIT_PARSE_ERROR
+}
+
+fn solve<W: Write>(
+    dims: PosCoords,
+    mode: SearchMode,
+    format: OutputFormat,
+    limit: Option<usize>,
+    out: &mut W,
+) -> io::Result<()> {
+    let mut solver = Solver::from(Board::from(dims));
+    solver.set_search_mode(mode);
+    let solutions = match limit {
+        Some(limit) => solver
+            .solutions()
+            .take(limit)
+            .map(|board| board.get_queen_positions())
+            .collect::<Vec<CoordList>>(),
+        None => solver.solve_sorted().solutions().to_vec(),
+    };
+    let solutions = solutions.iter();
+    match format {
+        OutputFormat::Board => {
+            for (i, queen_positions) in solutions.enumerate() {
+                if i > 0 {
+                    writeln!(out)?;
+                }
+                let board = Board::with_queens(dims, queen_positions.clone());
+                writeln!(out, "{}", board.to_string())?;
+            }
+        }
+        OutputFormat::Coords => {
+            for queen_positions in solutions {
+                let coords = queen_positions
+                    .iter()
+                    .map(|pos| format!("{:?}", pos))
+                    .collect::<Vec<String>>();
+                writeln!(out, "{}", coords.join(" "))?;
+            }
+        }
+        OutputFormat::Permutation => {
+            for queen_positions in solutions {
+                let perm = Permutation::from_coords(queen_positions, dims.0)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                writeln!(out, "{}", perm)?;
+            }
+        }
+        OutputFormat::Svg => {
+            let gallery = SolutionSet::new(dims, solutions.cloned().collect());
+            let svg = SvgRenderer::default().render_gallery(&gallery, GALLERY_COLUMNS);
+            write!(out, "{}", svg)?;
+        }
+    }
+    Ok(())
+}
+
+fn count<W: Write>(dims: PosCoords, threads: usize, out: &mut W) -> io::Result<()> {
+    let count = match threads {
+        0 | 1 => count_solutions(dims),
+        _ => {
+            let mut solver = Solver::from(Board::from(dims));
+            solver.set_search_mode(SearchMode::Bitboard);
+            solver.count_parallel(threads)
+        }
+    };
+    writeln!(out, "{}", count)
+}
+
+fn check<W: Write>(board: &Board, quiet: bool, out: &mut W) -> io::Result<i32> {
+    let CheckResult {
+        has_conflict,
+        is_solved,
+        num_queens,
+        num_free_spaces,
+    } = check_board(board);
+    if !quiet {
+        writeln!(out, "has_conflict: {}", has_conflict)?;
+        writeln!(out, "is_solved: {}", is_solved)?;
+        writeln!(out, "num_queens: {}", num_queens)?;
+        writeln!(out, "num_free_spaces: {}", num_free_spaces)?;
+        for attack in analyze_board(board).attacks {
+            let direction = match attack.direction {
+                AttackDirection::Row => "row",
+                AttackDirection::Column => "column",
+                AttackDirection::Diagonal => "diagonal",
+                AttackDirection::AntiDiagonal => "anti-diagonal",
+                AttackDirection::Leap => "leap",
+            };
+            writeln!(out, "attack: {:?} {:?} {}", attack.a, attack.b, direction)?;
+        }
+    }
+    Ok(if has_conflict { EXIT_CONFLICT } else { EXIT_OK })
+}
+
+/// Read and parse a board, returning a message describing any errors.
+fn read_board(input: &Input) -> Result<Board, String> {
+    let mut contents = String::new();
+    let res = match *input {
+        Input::Stdin => io::stdin().read_to_string(&mut contents),
+        Input::File(ref path) => {
+            File::open(path).and_then(|mut file| file.read_to_string(&mut contents))
+        }
+    };
+    res.map_err(|err| format!("could not read board: {}", err))?;
+    contents.parse::<Board>().map_err(|err| err.to_string())
+}
+
+/// Parse the command line arguments, not including the program name.
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (name, rest) = match args.split_first() {
+        Some((name, rest)) => (name.as_str(), rest),
+        None => return Err(String::from("missing command")),
+    };
+    let mut args = rest.iter().map(String::as_str);
+    let mut width = None;
+    let mut height = None;
+    let mut size = None;
+    let mut mode = SearchMode::Bitboard;
+    let mut format = OutputFormat::Board;
+    let mut limit = None;
+    let mut threads = 1;
+    let mut input = None;
+    let mut quiet = false;
+    let mut renderer = BoardRenderer::default();
+    let mut svg = false;
+    let mut rays = false;
+    while let Some(arg) = args.next() {
+        match (name, arg) {
+            ("solve", "-n") | ("solve", "--size") | ("count", "-n") | ("count", "--size") => {
+                size = Some(parse_number(arg, args.next())?);
+            }
+            ("solve", "--width") | ("count", "--width") => {
+                width = Some(parse_number(arg, args.next())?);
+            }
+            ("solve", "--height") | ("count", "--height") => {
+                height = Some(parse_number(arg, args.next())?);
+            }
+            ("solve", "--mode") => {
+                mode = match args.next() {
+                    Some("heuristic") => SearchMode::Heuristic,
+                    Some("exhaustive") => SearchMode::Exhaustive,
+                    Some("bitboard") => SearchMode::Bitboard,
+                    _ => {
+                        return Err(String::from(
+                            "--mode expects heuristic, exhaustive, or bitboard",
+                        ))
+                    }
+                }
+            }
+            ("solve", "--format") => {
+                format = match args.next() {
+                    Some("board") => OutputFormat::Board,
+                    Some("coords") => OutputFormat::Coords,
+                    Some("perm") => OutputFormat::Permutation,
+                    Some("svg") => OutputFormat::Svg,
+                    _ => return Err(String::from("--format expects board, coords, perm, or svg")),
+                }
+            }
+            ("solve", "--limit") => limit = Some(parse_number(arg, args.next())? as usize),
+            ("count", "--threads") => threads = parse_number(arg, args.next())? as usize,
+            ("check", "-q") | ("check", "--quiet") => quiet = true,
+            ("render", "--labels") => {
+                renderer.axis_labels = match args.next() {
+                    Some("none") => AxisLabels::None,
+                    Some("numeric") => AxisLabels::Numeric,
+                    Some("chess") => AxisLabels::Chess,
+                    _ => return Err(String::from("--labels expects none, numeric, or chess")),
+                }
+            }
+            ("render", "--contested") => renderer.show_contested = true,
+            ("render", "--conflicts") => renderer.highlight_conflicts = true,
+            ("render", "--unicode") => renderer.unicode = true,
+            ("render", "--checkerboard") => renderer.checkerboard = true,
+            ("render", "--svg") => svg = true,
+            ("render", "--rays") => rays = true,
+            ("check", path) | ("render", path) if input.is_none() && !is_flag(path) => {
+                input = Some(match path {
+                    "-" => Input::Stdin,
+                    _ => Input::File(String::from(path)),
+                });
+            }
+            _ => return Err(format!("unexpected argument '{}' for '{}'", arg, name)),
+        }
+    }
+    let dims = match (size, width, height) {
+        (Some(_), Some(_), _) | (Some(_), _, Some(_)) => {
+            return Err(String::from(
+                "--size cannot be used with --width or --height",
+            ))
+        }
+        (Some(n), None, None) => (n, n),
+        (None, width, height) => (width.unwrap_or(8), height.unwrap_or(8)),
+    };
+    if dims.0 == 0 || dims.1 == 0 {
+        return Err(String::from("the board must have at least one square"));
+    }
+    if format == OutputFormat::Permutation && dims.0 != dims.1 {
+        return Err(String::from("--format perm requires a square board"));
+    }
+    let input = input.unwrap_or(Input::Stdin);
+    match name {
+        "solve" => Ok(Command::Solve {
+            dims,
+            mode,
+            format,
+            limit,
+        }),
+        "count" => Ok(Command::Count { dims, threads }),
+        "check" => Ok(Command::Check { input, quiet }),
+        "render" => {
+            let svg = match svg {
+                true => Some(SvgRenderer {
+                    show_attack_rays: rays,
+                    highlight_conflicts: renderer.highlight_conflicts,
+                    ..SvgRenderer::default()
+                }),
+                false => None,
+            };
+            Ok(Command::Render {
+                input,
+                renderer,
+                svg,
+            })
+        }
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        _ => Err(format!("unknown command '{}'", name)),
+    }
+}
+
+fn is_flag(arg: &str) -> bool {
+    arg.starts_with('-') && arg != "-"
+}
+
+fn parse_number(flag: &str, value: Option<&str>) -> Result<u32, String> {
+    value
+        .and_then(|value| value.parse::<u32>().ok())
+        .ok_or_else(|| format!("{} expects a number", flag))
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    fn run_to_string(command: Command) -> (i32, String) {
+        let mut out = Vec::new();
+        let code = run(command, &mut out);
+        (code, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn solve_arguments_are_parsed() {
+        let command = parse_args(&args("solve -n 6 --mode exhaustive --format coords")).unwrap();
+        let expected = Command::Solve {
+            dims: (6, 6),
+            mode: SearchMode::Exhaustive,
+            format: OutputFormat::Coords,
+            limit: None,
+        };
+        assert_eq!(command, expected);
+    }
+
+    #[test]
+    fn board_size_defaults_to_8_by_8() {
+        let command = parse_args(&args("count --width 5")).unwrap();
+        let expected = Command::Count {
+            dims: (5, 8),
+            threads: 1,
+        };
+        assert_eq!(command, expected);
+    }
+
+    #[test]
+    fn render_arguments_are_parsed() {
+        let command = parse_args(&args("render board.txt --labels chess --unicode")).unwrap();
+        let renderer = BoardRenderer {
+            axis_labels: AxisLabels::Chess,
+            unicode: true,
+            ..BoardRenderer::default()
+        };
+        let expected = Command::Render {
+            input: Input::File(String::from("board.txt")),
+            renderer,
+            svg: None,
+        };
+        assert_eq!(command, expected);
+    }
+
+    #[test]
+    fn svg_render_arguments_are_parsed() {
+        let command = parse_args(&args("render --svg --rays --conflicts")).unwrap();
+        let svg = SvgRenderer {
+            show_attack_rays: true,
+            highlight_conflicts: true,
+            ..SvgRenderer::default()
+        };
+        match command {
+            Command::Render {
+                input, svg: res, ..
+            } => {
+                assert_eq!(input, Input::Stdin);
+                assert_eq!(res, Some(svg));
+            }
+            _ => panic!("expected a render command"),
+        }
+    }
+
+    #[test]
+    fn solve_prints_permutations() {
+        let command = parse_args(&args("solve -n 4 --format perm")).unwrap();
+        let (code, output) = run_to_string(command);
+        assert_eq!((code, output.as_str()), (EXIT_OK, "2413\n3142\n"));
+    }
+
+    #[test]
+    fn solve_prints_svg_gallery() {
+        let command = parse_args(&args("solve -n 6 --format svg --limit 3")).unwrap();
+        let (code, output) = run_to_string(command);
+        assert_eq!(code, EXIT_OK);
+        assert!(output.starts_with("<svg "));
+        assert!(output.contains("Solution 3 of 3"));
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert!(parse_args(&args("")).is_err());
+        assert!(parse_args(&args("solve --threads 2")).is_err());
+        assert!(parse_args(&args("count -n six")).is_err());
+        assert!(parse_args(&args("count -n 6 --width 4")).is_err());
+        assert!(parse_args(&args("check a.txt b.txt")).is_err());
+        assert!(parse_args(&args("solve -n 0")).is_err());
+        assert!(parse_args(&args("solve --width 4 --format perm")).is_err());
+        assert!(parse_args(&args("unknown")).is_err());
+    }
+
+    #[test]
+    fn solve_prints_solutions() {
+        let command = parse_args(&args("solve -n 4 --format coords")).unwrap();
+        let (code, output) = run_to_string(command);
+        assert_eq!(code, EXIT_OK);
+        let expected = "(0, 1) (1, 3) (2, 0) (3, 2)\n(0, 2) (1, 0) (2, 3) (3, 1)\n";
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn count_prints_number_of_solutions() {
+        let (code, output) = run_to_string(parse_args(&args("count -n 8")).unwrap());
+        assert_eq!((code, output.as_str()), (EXIT_OK, "92\n"));
+        let (code, output) = run_to_string(parse_args(&args("count -n 8 --threads 2")).unwrap());
+        assert_eq!((code, output.as_str()), (EXIT_OK, "92\n"));
+    }
+
+    #[test]
+    fn check_returns_exit_code_for_conflicts() {
+        let valid = "..Q.\nQ...\n...Q\n.Q..".parse::<Board>().unwrap();
+        let conflict = "..Q.\nQ...\n..Q.\n.Q..".parse::<Board>().unwrap();
+        let mut out = Vec::new();
+        assert_eq!(check(&valid, false, &mut out).unwrap(), EXIT_OK);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("is_solved: true"));
+        assert!(!output.contains("attack:"));
+        let mut out = Vec::new();
+        assert_eq!(check(&conflict, false, &mut out).unwrap(), EXIT_CONFLICT);
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("attack: (1, 0) (2, 1) diagonal\n"));
+        assert!(output.contains("attack: (2, 1) (2, 3) column\n"));
+        let mut out = Vec::new();
+        assert_eq!(check(&conflict, true, &mut out).unwrap(), EXIT_CONFLICT);
+        assert!(out.is_empty());
+    }
+
+    /// The first solutions are printed without searching for the others,
+    /// which would take far longer on a 20x20 board.
+    #[test]
+    fn solve_limit_stops_the_search() {
+        let command = parse_args(&args("solve -n 20 --format coords --limit 2")).unwrap();
+        let (code, output) = run_to_string(command);
+        assert_eq!(code, EXIT_OK);
+        assert_eq!(output.lines().count(), 2);
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_errors_return_io_error() {
+        let command = parse_args(&args("count -n 4")).unwrap();
+        assert_eq!(run(command, &mut FailingWriter), EXIT_IO_ERROR);
+    }
+
+    #[test]
+    fn unreadable_board_returns_parse_error() {
+        let input = Input::File(String::from("/nonexistent/board.txt"));
+        let command = Command::Check {
+            input,
+            quiet: false,
+        };
+        assert_eq!(run_to_string(command).0, EXIT_PARSE_ERROR);
+    }
+}
@@ -0,0 +1,87 @@
+use super::Board;
+use {Piece, Topology};
+
+impl Board {
+    /// Construct a solution for an n x n board directly, in O(n) time. Returns
+    /// `None` if no solution exists, which is the case for n = 0, 2, and 3.
+    ///
+    /// This uses the explicit construction described by Hoffman, Loessi, and
+    /// Moore. Numbering the rows from 1, list the even rows followed by the
+    /// odd rows, and place the queen for each column on the next row in the
+    /// list. This is a solution unless n mod 6 is 2 or 3, in which case the
+    /// lists are adjusted:
+    ///
+    /// - If n mod 6 is 2, swap 1 and 3 in the odd rows, and move 5 to the end.
+    /// - If n mod 6 is 3, move 2 to the end of the even rows, and move 1 and
+    ///   3 to the end of the odd rows.
+    pub fn constructive_solution(n: u32) -> Option<Board> {
+        if n == 0 || n == 2 || n == 3 {
+            return None;
+        }
+        let evens = (1..=n / 2).map(|i| 2 * i);
+        let odds = (0..n.div_ceil(2)).map(|i| 2 * i + 1);
+        let rows: Vec<u32> = match n % 6 {
+            2 => evens
+                .chain(vec![3, 1])
+                .chain(odds.skip(3))
+                .chain(Some(5))
+                .collect(),
+            3 => evens
+                .skip(1)
+                .chain(Some(2))
+                .chain(odds.skip(2))
+                .chain(vec![1, 3])
+                .collect(),
+            _ => evens.chain(odds).collect(),
+        };
+        let queens = rows
+            .into_iter()
+            .enumerate()
+            .map(|(x, row)| (x as u32, row - 1))
+            .collect();
+        Some(Board {
+            width: n,
+            height: n,
+            queens,
+            blocked: Vec::new(),
+            piece: Piece::Queen,
+            topology: Topology::Flat,
+        })
+    }
+}
+
+#[cfg(test)]
+mod board_constructive_tests {
+    use checker::check_board;
+    use Board;
+
+    #[test]
+    fn constructive_solution_is_valid_for_n_up_to_1000() {
+        for n in 4..1001 {
+            let board = Board::constructive_solution(n).unwrap();
+            assert_eq!(board.dims(), (n, n));
+            assert!(check_board(&board).is_solved, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn constructive_solution_handles_small_boards() {
+        let board = Board::constructive_solution(1).unwrap();
+        assert_eq!(board.get_queen_positions(), vec![(0, 0)]);
+        assert_eq!(Board::constructive_solution(0), None);
+        assert_eq!(Board::constructive_solution(2), None);
+        assert_eq!(Board::constructive_solution(3), None);
+    }
+
+    /// Compare against the example given for n = 8, where n mod 6 is 2.
+    #[test]
+    fn constructive_solution_matches_8_queens_example() {
+        let board = Board::constructive_solution(8).unwrap();
+        let rows = board
+            .get_queen_positions()
+            .into_iter()
+            .map(|(_, y)| y + 1)
+            .collect::<Vec<u32>>();
+        assert_eq!(rows, vec![2, 4, 6, 8, 3, 1, 7, 5]);
+    }
+}
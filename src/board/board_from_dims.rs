@@ -1,6 +1,6 @@
 use std::convert::From;
 
-use {Board, PosCoords};
+use {Board, Piece, PosCoords, Topology};
 
 impl From<PosCoords> for Board {
     fn from(dims: PosCoords) -> Board {
@@ -9,6 +9,9 @@ impl From<PosCoords> for Board {
             height,
             width,
             queens: Vec::new(),
+            blocked: Vec::new(),
+            piece: Piece::Queen,
+            topology: Topology::Flat,
         }
     }
 }
@@ -1,14 +1,15 @@
 use super::Board;
-use position_types::*;
+use position_types::PosCoords;
 use std::iter::FromIterator;
 
+/// Collect positions into a default 8x8 board. As with `Board::with_queens`,
+/// repeated positions are only added once, and this panics if any of the
+/// positions are out of bounds. Use `Board::with_queens` to create a board
+/// with other dimensions, or `Board::try_from_positions` to handle positions
+/// that are out of bounds.
 impl FromIterator<PosCoords> for Board {
     fn from_iter<I: IntoIterator<Item = PosCoords>>(positions: I) -> Board {
-        let mut board = Board::new();
-        for pos in positions {
-            board.add_queen(pos);
-        }
-        board
+        Board::with_queens(Board::new().dims(), positions)
     }
 }
 
@@ -34,6 +35,19 @@ mod board_from_pos_iter_tests {
         let expected: CoordList = [(0, 0), (0, 1)].iter().cloned().collect();
         assert_eq!(b.get_queen_positions(), expected);
     }
+
+    #[test]
+    fn create_board_merges_repeated_queens() {
+        let b: Board = [(0, 0), (3, 7), (0, 0)].iter().cloned().collect();
+        assert_eq!(b.dims(), (8, 8));
+        assert_eq!(b.get_queen_positions(), vec![(0, 0), (3, 7)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn create_board_rejects_oob_queens() {
+        let _: Board = [(0, 0), (3, 8)].iter().cloned().collect();
+    }
 }
 
 #[cfg(test)]
@@ -43,9 +57,12 @@ mod board_from_pos_iter_benches {
     use self::test::Bencher;
     use super::Board;
     use rand::Rng;
+    use random::seeded_rng;
     use std::iter::empty;
     use PosCoords;
 
+    const RNG_SEED: u64 = 1;
+
     #[bench]
     fn time_create_board_from_empty_iter(bencher: &mut Bencher) {
         bencher.iter(|| {
@@ -71,7 +88,7 @@ mod board_from_pos_iter_benches {
     fn generate_random_coordinates() -> Vec<PosCoords> {
         let mut x_coords: Vec<u32> = (0..8).collect();
         let mut y_coords: Vec<u32> = (0..8).collect();
-        let mut rng = rand::thread_rng();
+        let mut rng = seeded_rng(RNG_SEED);
         rng.shuffle(&mut x_coords);
         rng.shuffle(&mut y_coords);
         (0..8)
@@ -0,0 +1,376 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use {Board, Piece, PosCoords, Square};
+
+static BORDERED_EMPTY_CHAR: char = ' ';
+static BORDERLESS_EMPTY_CHAR: char = '.';
+static BLOCKED_CHAR: char = '#';
+static HORIZONTAL_BORDER_CHAR: char = '-';
+static VERTICAL_BORDER_CHAR: char = '|';
+
+/// The kinds of errors that can occur when parsing a board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseBoardErrorKind {
+    /// The input did not contain any rows.
+    Empty,
+    /// A square contained a character other than a piece, a blocked square,
+    /// or an empty square, or a piece of a different kind to the others.
+    UnexpectedChar(char),
+    /// A row or a horizontal border was missing a border character.
+    MissingBorder,
+    /// A row or a border did not have the same width as the first row.
+    WidthMismatch { expected: u32, found: u32 },
+    /// The input ended before the bottom border of the board.
+    MissingBottomBorder,
+    /// The input continued after the bottom border of the board.
+    TrailingContent,
+}
+
+/// An error encountered while parsing a board. The line and column of the
+/// error are both 1-based.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseBoardError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseBoardErrorKind,
+}
+
+impl ParseBoardError {
+    fn new(line: usize, column: usize, kind: ParseBoardErrorKind) -> ParseBoardError {
+        ParseBoardError { line, column, kind }
+    }
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseBoardErrorKind::Empty => write!(f, "the board is empty"),
+            ParseBoardErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ParseBoardErrorKind::MissingBorder => write!(f, "missing border"),
+            ParseBoardErrorKind::WidthMismatch { expected, found } => {
+                write!(f, "expected a width of {}, found {}", expected, found)
+            }
+            ParseBoardErrorKind::MissingBottomBorder => write!(f, "missing bottom border"),
+            ParseBoardErrorKind::TrailingContent => write!(f, "unexpected content after the board"),
+        }
+    }
+}
+
+impl Error for ParseBoardError {}
+
+/// Parse a board from the bordered format produced by `to_string`, or from
+/// a border-less format using '.' for empty squares. In both formats, 'Q'
+/// represents a queen, '#' represents a blocked square, and the top row has
+/// the highest y-coordinate. Other pieces are represented by the symbols
+/// given by `Piece::symbol`, but every piece must be of the same kind. The
+/// dimensions of the board are inferred from the input.
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Board, ParseBoardError> {
+        let lines = s.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim_end_matches('\r')))
+            .skip_while(|&(_, line)| line.is_empty())
+            .collect::<Vec<(usize, &str)>>();
+        let (body, end_line) = split_trailing_empty_lines(&lines);
+        match body.first() {
+            None => Err(ParseBoardError::new(end_line, 1, ParseBoardErrorKind::Empty)),
+            Some(&(_, first)) if first.starts_with(HORIZONTAL_BORDER_CHAR) => {
+                parse_bordered(body, end_line)
+            }
+            Some(_) => parse_borderless(body),
+        }
+    }
+}
+
+/// Split the lines into the body of the board, and the line number following
+/// the last line of the body.
+fn split_trailing_empty_lines<'a>(lines: &'a [(usize, &'a str)]) -> (&'a [(usize, &'a str)], usize) {
+    let len = lines
+        .iter()
+        .rposition(|&(_, line)| !line.is_empty())
+        .map_or(0, |i| i + 1);
+    let body = &lines[..len];
+    let next_line = body.last().map_or(1, |&(i, _)| i + 1);
+    (body, next_line)
+}
+
+/// Parse a board surrounded by a border, as produced by `to_string`.
+fn parse_bordered(lines: &[(usize, &str)], end_line: usize) -> Result<Board, ParseBoardError> {
+    let (top_line, top_border) = lines[0];
+    let width = parse_horizontal_border(top_line, top_border, None)?;
+    let bottom = lines[1..]
+        .iter()
+        .position(|&(_, line)| line.starts_with(HORIZONTAL_BORDER_CHAR))
+        .map(|i| i + 1);
+    let bottom = match bottom {
+        Some(i) => i,
+        None => {
+            let kind = ParseBoardErrorKind::MissingBottomBorder;
+            return Err(ParseBoardError::new(end_line, 1, kind));
+        }
+    };
+    let (bottom_line, bottom_border) = lines[bottom];
+    parse_horizontal_border(bottom_line, bottom_border, Some(width))?;
+    if let Some(&(line, _)) = lines.get(bottom + 1) {
+        return Err(ParseBoardError::new(line, 1, ParseBoardErrorKind::TrailingContent));
+    }
+    let rows = &lines[1..bottom];
+    if rows.is_empty() {
+        return Err(ParseBoardError::new(bottom_line, 1, ParseBoardErrorKind::Empty));
+    }
+    let height = rows.len() as u32;
+    let mut squares = Vec::new();
+    let mut piece = None;
+    for (i, &(line_num, line)) in rows.iter().enumerate() {
+        let y = height - i as u32 - 1;
+        let chars = line.chars().collect::<Vec<char>>();
+        check_width(line_num, chars.len() as u32, width + 2)?;
+        if chars[0] != VERTICAL_BORDER_CHAR {
+            return Err(ParseBoardError::new(line_num, 1, ParseBoardErrorKind::MissingBorder));
+        }
+        if chars[chars.len() - 1] != VERTICAL_BORDER_CHAR {
+            let kind = ParseBoardErrorKind::MissingBorder;
+            return Err(ParseBoardError::new(line_num, chars.len(), kind));
+        }
+        let row = &chars[1..chars.len() - 1];
+        let row = parse_squares(line_num, 2, y, row, BORDERED_EMPTY_CHAR, &mut piece)?;
+        squares.extend(row);
+    }
+    Ok(form_board((width, height), squares, piece))
+}
+
+/// Parse a board without a border, using '.' to represent empty squares.
+fn parse_borderless(lines: &[(usize, &str)]) -> Result<Board, ParseBoardError> {
+    let width = lines[0].1.chars().count() as u32;
+    let height = lines.len() as u32;
+    let mut squares = Vec::new();
+    let mut piece = None;
+    for (i, &(line_num, line)) in lines.iter().enumerate() {
+        let y = height - i as u32 - 1;
+        let chars = line.chars().collect::<Vec<char>>();
+        check_width(line_num, chars.len() as u32, width)?;
+        let row = parse_squares(line_num, 1, y, &chars, BORDERLESS_EMPTY_CHAR, &mut piece)?;
+        squares.extend(row);
+    }
+    Ok(form_board((width, height), squares, piece))
+}
+
+/// Create a board from the pieces and blocked squares that were parsed.
+/// Boards without any pieces hold queens.
+fn form_board(dims: PosCoords, squares: Vec<(PosCoords, Square)>, piece: Option<Piece>) -> Board {
+    let blocked = squares
+        .iter()
+        .filter(|&&(_, square)| square == Square::Blocked)
+        .map(|&(pos, _)| pos);
+    let mut board = Board::with_blocked(dims, blocked);
+    board.queens = squares
+        .iter()
+        .filter(|&&(_, square)| square == Square::Queen)
+        .map(|&(pos, _)| pos)
+        .collect();
+    board.queens.sort();
+    board.piece = piece.unwrap_or_default();
+    board
+}
+
+/// Parse a horizontal border, returning the width of the board inside it.
+/// If the width is already known, check that the border matches it.
+fn parse_horizontal_border(
+    line_num: usize,
+    line: &str,
+    expected_width: Option<u32>,
+) -> Result<u32, ParseBoardError> {
+    if let Some(i) = line.chars().position(|c| c != HORIZONTAL_BORDER_CHAR) {
+        return Err(ParseBoardError::new(line_num, i + 1, ParseBoardErrorKind::MissingBorder));
+    }
+    let len = line.chars().count() as u32;
+    if len < 3 {
+        return Err(ParseBoardError::new(line_num, 1, ParseBoardErrorKind::Empty));
+    }
+    if let Some(width) = expected_width {
+        check_width(line_num, len, width + 2)?;
+    }
+    Ok(len - 2)
+}
+
+/// Check that a line has the expected number of characters.
+fn check_width(line_num: usize, found: u32, expected: u32) -> Result<(), ParseBoardError> {
+    match found == expected {
+        true => Ok(()),
+        false => {
+            let kind = ParseBoardErrorKind::WidthMismatch { expected, found };
+            let column = found.min(expected) as usize + 1;
+            Err(ParseBoardError::new(line_num, column, kind))
+        }
+    }
+}
+
+/// Parse the squares of a row, returning the positions of its queens. The
+/// `first_column` is the column of the first square within the line.
+fn parse_squares(
+    line_num: usize,
+    first_column: usize,
+    y: u32,
+    squares: &[char],
+    empty_char: char,
+    piece: &mut Option<Piece>,
+) -> Result<Vec<(PosCoords, Square)>, ParseBoardError> {
+    let mut parsed = Vec::new();
+    for (x, &c) in squares.iter().enumerate() {
+        let pos = (x as u32, y);
+        let square_piece = Piece::from_symbol(c);
+        let is_same_kind = square_piece.is_some_and(|p| piece.is_none_or(|prev| prev == p));
+        if is_same_kind {
+            *piece = square_piece;
+            parsed.push((pos, Square::Queen));
+        } else if c == BLOCKED_CHAR {
+            parsed.push((pos, Square::Blocked));
+        } else if c != empty_char {
+            let kind = ParseBoardErrorKind::UnexpectedChar(c);
+            return Err(ParseBoardError::new(line_num, first_column + x, kind));
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod board_from_str_tests {
+    use super::{ParseBoardError, ParseBoardErrorKind};
+    use std::string::ToString;
+    use {Board, Piece};
+
+    fn parse_err(s: &str) -> (usize, usize, ParseBoardErrorKind) {
+        let ParseBoardError { line, column, kind } = s.parse::<Board>().unwrap_err();
+        (line, column, kind)
+    }
+
+    #[test]
+    fn bordered_board_is_parsed() {
+        let s = [
+            "----------",
+            "|Q       |",
+            "|      Q |",
+            "|        |",
+            "|        |",
+            "|        |",
+            "|        |",
+            "|        |",
+            "|Q     Q |",
+            "----------",
+        ].join("\n");
+        let b = s.parse::<Board>().unwrap();
+        let expected = Board::with_queens((8, 8), vec![(0, 0), (0, 7), (6, 0), (6, 6)]);
+        assert_eq!(b, expected);
+    }
+
+    #[test]
+    fn borderless_board_is_parsed() {
+        let s = "..Q.\nQ...\n...Q\n.Q..\n";
+        let b = s.parse::<Board>().unwrap();
+        let expected = Board::with_queens((4, 4), vec![(0, 2), (1, 0), (2, 3), (3, 1)]);
+        assert_eq!(b, expected);
+    }
+
+    #[test]
+    fn to_string_output_round_trips() {
+        let boards = vec![
+            Board::new(),
+            Board::with_queens((8, 8), vec![(2, 0), (4, 1), (1, 2), (7, 3), (0, 4)]),
+            Board::with_queens((5, 3), vec![(0, 0), (4, 2)]),
+            Board::with_queens((1, 1), vec![(0, 0)]),
+            {
+                let mut b = Board::with_blocked((4, 3), vec![(1, 1), (3, 0)]);
+                b.add_queen((0, 2));
+                b
+            },
+        ];
+        boards.into_iter().for_each(|b| {
+            assert_eq!(b.to_string().parse::<Board>(), Ok(b));
+        });
+    }
+
+    #[test]
+    fn blocked_squares_are_parsed() {
+        let b = "-----\n|Q# |\n|  #|\n-----".parse::<Board>().unwrap();
+        assert_eq!(b.get_queen_positions(), vec![(0, 1)]);
+        assert_eq!(b.get_blocked_positions(), vec![(1, 1), (2, 0)]);
+        let b = "#Q.\n...\n".parse::<Board>().unwrap();
+        assert_eq!(b.get_queen_positions(), vec![(1, 1)]);
+        assert_eq!(b.get_blocked_positions(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn other_pieces_are_parsed() {
+        let b = "-----\n|N  |\n|  N|\n-----".parse::<Board>().unwrap();
+        assert_eq!(b.piece(), Piece::Knight);
+        assert_eq!(b.get_queen_positions(), vec![(0, 1), (2, 0)]);
+        assert_eq!(b.to_string().parse::<Board>(), Ok(b));
+        assert_eq!(
+            parse_err("B..\n..R\n"),
+            (2, 3, ParseBoardErrorKind::UnexpectedChar('R'))
+        );
+    }
+
+    #[test]
+    fn dimensions_are_inferred() {
+        let b = "-------\n|     |\n|  Q  |\n-------".parse::<Board>().unwrap();
+        assert_eq!(b.dims(), (5, 2));
+        assert_eq!(b.get_queen_positions(), vec![(2, 0)]);
+        let b = "...\n...\n...\n.Q.\n".parse::<Board>().unwrap();
+        assert_eq!(b.dims(), (3, 4));
+    }
+
+    #[test]
+    fn surrounding_empty_lines_are_ignored() {
+        let b = "\n\n---\n|Q|\n---\n\n".parse::<Board>().unwrap();
+        assert_eq!(b, Board::with_queens((1, 1), vec![(0, 0)]));
+    }
+
+    #[test]
+    fn errors_report_line_and_column() {
+        assert_eq!(parse_err(""), (1, 1, ParseBoardErrorKind::Empty));
+        assert_eq!(
+            parse_err("-----\n|Q x|\n-----"),
+            (2, 4, ParseBoardErrorKind::UnexpectedChar('x'))
+        );
+        assert_eq!(
+            parse_err("-----\n|Q  |\n Q  |\n-----"),
+            (3, 1, ParseBoardErrorKind::MissingBorder)
+        );
+        assert_eq!(
+            parse_err("-----\n|Q  |\n|Q  :\n-----"),
+            (3, 5, ParseBoardErrorKind::MissingBorder)
+        );
+        assert_eq!(
+            parse_err("-----\n|Q  |\n|Q |\n-----"),
+            (3, 5, ParseBoardErrorKind::WidthMismatch { expected: 5, found: 4 })
+        );
+        assert_eq!(
+            parse_err("-----\n|Q  |\n|   |\n\n"),
+            (4, 1, ParseBoardErrorKind::MissingBottomBorder)
+        );
+        assert_eq!(
+            parse_err("-----\n|Q  |\n-----\n|   |"),
+            (4, 1, ParseBoardErrorKind::TrailingContent)
+        );
+        assert_eq!(
+            parse_err("..Q\n.Q..\n"),
+            (2, 4, ParseBoardErrorKind::WidthMismatch { expected: 3, found: 4 })
+        );
+        assert_eq!(
+            parse_err("..Q\n.q.\n"),
+            (2, 2, ParseBoardErrorKind::UnexpectedChar('q'))
+        );
+    }
+
+    #[test]
+    fn errors_can_be_displayed() {
+        let err = "..Q\n.q.\n".parse::<Board>().unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: unexpected character 'q'");
+    }
+}
@@ -1,65 +1,21 @@
-use {Board, PosCoords};
+use {Board, Symmetry};
 
 impl Board {
     /// Return a vector of Boards representing reflections of the current state.
     pub fn get_reflections(&self) -> Vec<Board> {
-        vec![
-            self.get_horizontal_reflection(),
-            self.get_vertical_reflection(),
-            self.get_inverse(),
-        ]
-    }
-
-    /// Reflect the board horizontally on the y-axis.
-    fn get_horizontal_reflection(&self) -> Board {
-        self.queens
-            .iter()
-            .map(|pos| self.get_pos_horizontal_reflection(pos))
-            .collect()
-    }
-
-    /// Reflect the board vertically on the y-axis.
-    fn get_vertical_reflection(&self) -> Board {
-        self.queens
-            .iter()
-            .map(|pos| self.get_pos_vertical_reflection(pos))
-            .collect()
-    }
-
-    /// Reflect the board on both axes.
-    fn get_inverse(&self) -> Board {
-        self.queens
-            .iter()
-            .map(|pos| self.get_pos_inverse(pos))
+        [
+            Symmetry::ReflectHorizontal,
+            Symmetry::ReflectVertical,
+            Symmetry::Rotate180,
+        ].iter()
+            .filter_map(|&s| self.apply_symmetry(s))
             .collect()
     }
-
-    /// Reflect a position horizontally on the x-axis.
-    fn get_pos_horizontal_reflection(&self, pos: &PosCoords) -> PosCoords {
-        let &(orig_x, y) = pos;
-        let new_x = self.width - orig_x - 1;
-        (new_x, y)
-    }
-
-    /// Reflect a position vertically on the y-axis.
-    fn get_pos_vertical_reflection(&self, pos: &PosCoords) -> PosCoords {
-        let &(x, orig_y) = pos;
-        let new_y = self.height - orig_y - 1;
-        (x, new_y)
-    }
-
-    /// Reflect a position vertically and horizontally.
-    fn get_pos_inverse(&self, pos: &PosCoords) -> PosCoords {
-        let &(orig_x, orig_y) = pos;
-        let new_y = self.height - orig_y - 1;
-        let new_x = self.width - orig_x - 1;
-        (new_x, new_y)
-    }
 }
 
 #[cfg(test)]
 mod pos_reflect_tests {
-    use {Board, PosCoords};
+    use {PosCoords, Symmetry};
 
     struct RotateTestCase {
         input: PosCoords,
@@ -85,7 +41,7 @@ mod pos_reflect_tests {
 
     #[test]
     fn position_reflections_work() {
-        let b = Board::new();
+        let dims = (8, 8);
         TEST_CASES.iter().for_each(|test_case| {
             let &RotateTestCase {
                 input,
@@ -94,13 +50,13 @@ mod pos_reflect_tests {
                 expected_inverse,
             } = test_case;
 
-            let actual_horizontal = b.get_pos_horizontal_reflection(&input);
+            let actual_horizontal = Symmetry::ReflectHorizontal.apply_to_pos(input, dims);
             assert_eq!(actual_horizontal, expected_horizontal);
 
-            let actual_inverse = b.get_pos_inverse(&input);
+            let actual_inverse = Symmetry::Rotate180.apply_to_pos(input, dims);
             assert_eq!(actual_inverse, expected_inverse);
 
-            let actual_vertical = b.get_pos_vertical_reflection(&input);
+            let actual_vertical = Symmetry::ReflectVertical.apply_to_pos(input, dims);
             assert_eq!(actual_vertical, expected_vertical);
         })
     }
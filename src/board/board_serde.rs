@@ -0,0 +1,133 @@
+use std::convert::TryFrom;
+
+use super::Board;
+use {BoardError, CoordList, Piece, PosError, Topology};
+
+/// The serialized form of a board. Deserialized queens must be in bounds and
+/// not on a blocked square, but may conflict or exceed the board's capacity,
+/// so that any board that can be serialized can be deserialized again.
+#[derive(Deserialize)]
+pub(super) struct BoardData {
+    width: u32,
+    height: u32,
+    queens: CoordList,
+    #[serde(default)]
+    blocked: CoordList,
+    #[serde(default)]
+    piece: Piece,
+    #[serde(default)]
+    topology: Topology,
+}
+
+impl TryFrom<BoardData> for Board {
+    type Error = BoardError;
+
+    fn try_from(data: BoardData) -> Result<Board, BoardError> {
+        let mut board = Board::from((data.width, data.height));
+        board.set_piece(data.piece);
+        board.set_topology(data.topology);
+        for pos in data.blocked {
+            board.try_add_blocked(pos)?;
+        }
+        for pos in data.queens {
+            if !board.in_bounds(&pos) {
+                return Err(BoardError::from((PosError::OutOfBounds, pos)));
+            }
+            if board.is_blocked(&pos) {
+                return Err(BoardError::BlockedSquare(pos));
+            }
+            board.queens.push(pos);
+        }
+        board.queens.sort();
+        board.queens.dedup();
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod board_serde_tests {
+    use checker::check_board;
+    use serde_json;
+    use {Board, Piece, Topology};
+
+    #[test]
+    fn board_is_serialized_as_dims_and_queens() {
+        let board = Board::with_queens((4, 3), vec![(3, 0), (0, 1)]);
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, r#"{"width":4,"height":3,"queens":[[0,1],[3,0]]}"#);
+    }
+
+    #[test]
+    fn board_round_trip_works() {
+        let board = Board::with_queens((8, 8), vec![(0, 0), (4, 1), (7, 2), (5, 3)]);
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    }
+
+    #[test]
+    fn deserialized_queens_are_sorted() {
+        let json = r#"{"width":8,"height":8,"queens":[[5,3],[0,0]]}"#;
+        let board = serde_json::from_str::<Board>(json).unwrap();
+        assert_eq!(board.get_queen_positions(), vec![(0, 0), (5, 3)]);
+    }
+
+    #[test]
+    fn blocked_squares_are_serialized() {
+        let mut board = Board::with_blocked((3, 3), vec![(1, 1)]);
+        board.add_queen((0, 0));
+        let json = serde_json::to_string(&board).unwrap();
+        let expected = r#"{"width":3,"height":3,"queens":[[0,0]],"blocked":[[1,1]]}"#;
+        assert_eq!(json, expected);
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    }
+
+    #[test]
+    fn pieces_are_serialized() {
+        let mut board = Board::with_queens((2, 2), vec![(0, 0), (0, 1)]);
+        board.set_piece(Piece::Bishop);
+        let json = serde_json::to_string(&board).unwrap();
+        let expected = r#"{"width":2,"height":2,"queens":[[0,0],[0,1]],"piece":"Bishop"}"#;
+        assert_eq!(json, expected);
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    }
+
+    #[test]
+    fn toroidal_boards_are_serialized() {
+        let mut board = Board::with_queens((5, 5), vec![(0, 0), (1, 2)]);
+        board.set_topology(Topology::Toroidal);
+        let json = serde_json::to_string(&board).unwrap();
+        let expected = r#"{"width":5,"height":5,"queens":[[0,0],[1,2]],"topology":"Toroidal"}"#;
+        assert_eq!(json, expected);
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    }
+
+    /// Boards holding more queens than they can solve are checked, rather
+    /// than rejected, so they survive a round trip.
+    #[test]
+    fn overfull_board_round_trip_works() {
+        let board = Board::with_queens((2, 2), vec![(0, 0), (0, 1), (1, 0)]);
+        assert!(check_board(&board).has_conflict);
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, r#"{"width":2,"height":2,"queens":[[0,0],[0,1],[1,0]]}"#);
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    }
+
+    #[test]
+    fn repeated_queens_are_merged() {
+        let json = r#"{"width":4,"height":4,"queens":[[1,1],[1,1]]}"#;
+        let board = serde_json::from_str::<Board>(json).unwrap();
+        assert_eq!(board.get_queen_positions(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn invalid_boards_are_rejected() {
+        let invalid = [
+            r#"{"width":4,"height":4,"queens":[[4,0]]}"#,
+            r#"{"width":4,"queens":[]}"#,
+            r#"{"width":4,"height":4,"queens":[[1,1]],"blocked":[[1,1]]}"#,
+        ];
+        for json in invalid.iter() {
+            assert!(serde_json::from_str::<Board>(json).is_err(), "{}", json);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use {Board, PosCoords};
+
+/// The symmetries of a chess board, forming the dihedral group of order 8.
+/// Rotations are clockwise, with the y-axis pointing up.
+///
+/// Symmetries that swap the axes of the board (the 90 and 270 degree
+/// rotations, and both diagonal reflections) are only defined for square
+/// boards, since they would not map a non-square board onto itself.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Reflect the board across its vertical axis, mirroring the x-coordinates.
+    ReflectHorizontal,
+    /// Reflect the board across its horizontal axis, mirroring the y-coordinates.
+    ReflectVertical,
+    /// Reflect the board across the diagonal running through (0, 0).
+    ReflectDiagonal,
+    /// Reflect the board across the anti-diagonal, perpendicular to the diagonal.
+    ReflectAntiDiagonal,
+}
+
+/// Each symmetry can be described as an optional transpose (swapping the
+/// x and y coordinates), followed by optionally mirroring the x-coordinate
+/// and the y-coordinate. This struct is used to compose and invert them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Transform {
+    transpose: bool,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+static SYMMETRIES: &[Symmetry] = &[
+    Symmetry::Identity,
+    Symmetry::Rotate90,
+    Symmetry::Rotate180,
+    Symmetry::Rotate270,
+    Symmetry::ReflectHorizontal,
+    Symmetry::ReflectVertical,
+    Symmetry::ReflectDiagonal,
+    Symmetry::ReflectAntiDiagonal,
+];
+
+impl Symmetry {
+    /// Return a slice containing all eight symmetries.
+    pub fn all() -> &'static [Symmetry] {
+        SYMMETRIES
+    }
+
+    /// Returns true if the symmetry swaps the axes of the board.
+    pub fn swaps_axes(self) -> bool {
+        self.transform().transpose
+    }
+
+    /// Returns true if the symmetry maps a board with the given dimensions
+    /// onto itself.
+    pub fn is_defined_for(self, dims: PosCoords) -> bool {
+        !self.swaps_axes() || dims.0 == dims.1
+    }
+
+    /// Return the symmetry equivalent to applying `self`, then `other`.
+    pub fn compose(self, other: Symmetry) -> Symmetry {
+        let (a, b) = (self.transform(), other.transform());
+        // Mirroring x before a transpose is the same as mirroring y after it.
+        let (flip_x, flip_y) = match b.transpose {
+            true => (a.flip_y, a.flip_x),
+            false => (a.flip_x, a.flip_y),
+        };
+        Symmetry::from_transform(Transform {
+            transpose: a.transpose ^ b.transpose,
+            flip_x: flip_x ^ b.flip_x,
+            flip_y: flip_y ^ b.flip_y,
+        })
+    }
+
+    /// Return the symmetry that undoes this symmetry.
+    pub fn inverse(self) -> Symmetry {
+        let t = self.transform();
+        let (flip_x, flip_y) = match t.transpose {
+            true => (t.flip_y, t.flip_x),
+            false => (t.flip_x, t.flip_y),
+        };
+        Symmetry::from_transform(Transform {
+            transpose: t.transpose,
+            flip_x,
+            flip_y,
+        })
+    }
+
+    /// Apply the symmetry to a position on a board with the given dimensions.
+    /// Symmetries that swap the axes map the position onto a board with the
+    /// width and height swapped.
+    pub fn apply_to_pos(self, pos: PosCoords, dims: PosCoords) -> PosCoords {
+        let t = self.transform();
+        let ((x, y), (width, height)) = match t.transpose {
+            true => ((pos.1, pos.0), (dims.1, dims.0)),
+            false => (pos, dims),
+        };
+        let new_x = if t.flip_x { width - x - 1 } else { x };
+        let new_y = if t.flip_y { height - y - 1 } else { y };
+        (new_x, new_y)
+    }
+
+    fn transform(self) -> Transform {
+        let (transpose, flip_x, flip_y) = match self {
+            Symmetry::Identity => (false, false, false),
+            Symmetry::Rotate90 => (true, false, true),
+            Symmetry::Rotate180 => (false, true, true),
+            Symmetry::Rotate270 => (true, true, false),
+            Symmetry::ReflectHorizontal => (false, true, false),
+            Symmetry::ReflectVertical => (false, false, true),
+            Symmetry::ReflectDiagonal => (true, false, false),
+            Symmetry::ReflectAntiDiagonal => (true, true, true),
+        };
+        Transform {
+            transpose,
+            flip_x,
+            flip_y,
+        }
+    }
+
+    fn from_transform(t: Transform) -> Symmetry {
+        *SYMMETRIES
+            .iter()
+            .find(|s| s.transform() == t)
+            .expect("Every transform corresponds to a symmetry")
+    }
+}
+
+impl Board {
+    /// Apply a symmetry to the board. Returns `None` if the symmetry is not
+    /// defined for the dimensions of the board. Blocked squares are mapped
+    /// along with the queens.
+    pub fn apply_symmetry(&self, symmetry: Symmetry) -> Option<Board> {
+        if !symmetry.is_defined_for(self.dims()) {
+            return None;
+        }
+        let dims = self.dims();
+        let apply = |positions: &[PosCoords]| {
+            let mut image = positions
+                .iter()
+                .map(|&pos| symmetry.apply_to_pos(pos, dims))
+                .collect::<Vec<PosCoords>>();
+            image.sort();
+            image
+        };
+        Some(Board {
+            queens: apply(&self.queens),
+            blocked: apply(&self.blocked),
+            ..self.clone()
+        })
+    }
+
+    /// Return the images of the board under every symmetry that is defined
+    /// for its dimensions, including the identity.
+    pub fn get_symmetries(&self) -> Vec<(Symmetry, Board)> {
+        Symmetry::all()
+            .iter()
+            .filter_map(|&s| self.apply_symmetry(s).map(|board| (s, board)))
+            .collect()
+    }
+
+    /// Return the canonical form of the board. This is the image of the board
+    /// under its symmetries with the lexicographically smallest list of queen
+    /// positions, so boards in the same orbit share a canonical form. Ties
+    /// are broken using the positions of the blocked squares.
+    pub fn canonical_form(&self) -> Board {
+        self.get_symmetries()
+            .into_iter()
+            .map(|(_, board)| board)
+            .min_by(|a, b| (&a.queens, &a.blocked).cmp(&(&b.queens, &b.blocked)))
+            .expect("The identity is defined for every board")
+    }
+
+    /// Return the number of distinct boards that this board can be mapped to
+    /// using its symmetries, including itself.
+    pub fn orbit_size(&self) -> usize {
+        let mut images = self.get_symmetries()
+            .into_iter()
+            .map(|(_, board)| (board.queens, board.blocked))
+            .collect::<Vec<_>>();
+        images.sort();
+        images.dedup();
+        images.len()
+    }
+}
+
+#[cfg(test)]
+mod symmetry_tests {
+    use super::Symmetry;
+    use {Board, PosCoords};
+
+    /// Images of (0, 7) and (2, 6) on an 8x8 board, from the examples in
+    /// `docs/add-rotations-and-reflections.md`.
+    static TEST_CASES: &[(Symmetry, PosCoords, PosCoords)] = &[
+        (Symmetry::Identity, (0, 7), (2, 6)),
+        (Symmetry::Rotate90, (7, 7), (6, 5)),
+        (Symmetry::Rotate180, (7, 0), (5, 1)),
+        (Symmetry::Rotate270, (0, 0), (1, 2)),
+        (Symmetry::ReflectHorizontal, (7, 7), (5, 6)),
+        (Symmetry::ReflectVertical, (0, 0), (2, 1)),
+        (Symmetry::ReflectDiagonal, (7, 0), (6, 2)),
+        (Symmetry::ReflectAntiDiagonal, (0, 7), (1, 5)),
+    ];
+
+    #[test]
+    fn symmetries_map_positions_correctly() {
+        let dims = (8, 8);
+        TEST_CASES.iter().for_each(|&(s, expected_a, expected_b)| {
+            assert_eq!(s.apply_to_pos((0, 7), dims), expected_a, "{:?}", s);
+            assert_eq!(s.apply_to_pos((2, 6), dims), expected_b, "{:?}", s);
+        });
+    }
+
+    #[test]
+    fn composition_matches_applying_in_sequence() {
+        let (dims, pos) = ((8, 8), (2, 5));
+        for &a in Symmetry::all() {
+            for &b in Symmetry::all() {
+                let expected = b.apply_to_pos(a.apply_to_pos(pos, dims), dims);
+                assert_eq!(a.compose(b).apply_to_pos(pos, dims), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn composing_with_inverse_gives_identity() {
+        for &s in Symmetry::all() {
+            assert_eq!(s.compose(s.inverse()), Symmetry::Identity);
+            assert_eq!(s.inverse().compose(s), Symmetry::Identity);
+        }
+    }
+
+    #[test]
+    fn four_quarter_turns_give_identity() {
+        let r = Symmetry::Rotate90;
+        assert_eq!(r.compose(r), Symmetry::Rotate180);
+        assert_eq!(r.compose(r).compose(r), Symmetry::Rotate270);
+        assert_eq!(r.compose(r).compose(r).compose(r), Symmetry::Identity);
+        assert_eq!(r.inverse(), Symmetry::Rotate270);
+    }
+
+    #[test]
+    fn axis_swapping_symmetries_are_undefined_for_non_square_boards() {
+        let b: Board = Board::from((3, 5));
+        for &s in Symmetry::all() {
+            assert_eq!(b.apply_symmetry(s).is_some(), !s.swaps_axes());
+        }
+        assert_eq!(b.get_symmetries().len(), 4);
+        assert_eq!(Board::new().get_symmetries().len(), 8);
+    }
+
+    #[test]
+    fn non_square_board_reflections_keep_dimensions() {
+        let mut b = Board::from((3, 5));
+        b.add_queen((0, 4));
+        let reflected = b.apply_symmetry(Symmetry::Rotate180).unwrap();
+        assert_eq!(reflected.dims(), (3, 5));
+        assert_eq!(reflected.get_queen_positions(), vec![(2, 0)]);
+    }
+
+    #[test]
+    fn blocked_squares_are_mapped_with_queens() {
+        let mut b = Board::with_blocked((8, 8), vec![(1, 0)]);
+        b.add_queen((0, 7));
+        let rotated = b.apply_symmetry(Symmetry::Rotate90).unwrap();
+        assert_eq!(rotated.get_queen_positions(), vec![(7, 7)]);
+        assert_eq!(rotated.get_blocked_positions(), vec![(0, 6)]);
+    }
+}
+
+#[cfg(test)]
+mod canonical_form_tests {
+    use Board;
+
+    #[test]
+    fn symmetric_boards_share_canonical_form() {
+        let b: Board = [(0, 7), (2, 6)].iter().cloned().collect();
+        let canonical = b.canonical_form();
+        assert_eq!(canonical.get_queen_positions(), vec![(0, 0), (1, 2)]);
+        b.get_symmetries().into_iter().for_each(|(_, image)| {
+            assert_eq!(image.canonical_form(), canonical);
+        });
+    }
+
+    #[test]
+    fn orbit_sizes_are_correct() {
+        let empty = Board::new();
+        assert_eq!(empty.orbit_size(), 1);
+        let center: Board = [(3, 3), (4, 4)].iter().cloned().collect();
+        assert_eq!(center.orbit_size(), 2);
+        let corner: Board = [(0, 0)].iter().cloned().collect();
+        assert_eq!(corner.orbit_size(), 4);
+        let b: Board = [(0, 7), (2, 6)].iter().cloned().collect();
+        assert_eq!(b.orbit_size(), 8);
+    }
+
+    #[test]
+    fn blocked_squares_are_part_of_the_orbit() {
+        let b = Board::with_blocked((8, 8), vec![(3, 3), (4, 4)]);
+        assert_eq!(b.orbit_size(), 2);
+        let b = Board::with_blocked((8, 8), vec![(0, 1)]);
+        assert_eq!(b.orbit_size(), 8);
+        let canonical = b.canonical_form();
+        b.get_symmetries().into_iter().for_each(|(_, image)| {
+            assert_eq!(image.canonical_form(), canonical);
+        });
+    }
+}
@@ -3,7 +3,7 @@ use std::iter::FromIterator;
 use std::string::ToString;
 
 static EMPTY_CHAR: char = ' ';
-static QUEEN_CHAR: char = 'Q';
+static BLOCKED_CHAR: char = '#';
 
 impl ToString for Board {
     fn to_string(&self) -> String {
@@ -19,7 +19,10 @@ impl Board {
         let (width, height) = self.dims();
         let mut chars = vec![vec![EMPTY_CHAR; width as usize]; height as usize];
         for (x, y) in self.get_queen_positions().into_iter() {
-            chars[y as usize][x as usize] = QUEEN_CHAR;
+            chars[y as usize][x as usize] = self.piece().symbol();
+        }
+        for (x, y) in self.get_blocked_positions().into_iter() {
+            chars[y as usize][x as usize] = BLOCKED_CHAR;
         }
         chars
     }
@@ -98,4 +101,12 @@ mod board_to_string_tests {
         ].join("\n");
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn blocked_squares_are_drawn() {
+        let mut b = Board::with_blocked((3, 2), vec![(1, 0)]);
+        b.add_queen((2, 1));
+        let expected = ["-----", "|  Q|", "| # |", "-----"].join("\n");
+        assert_eq!(b.to_string(), expected);
+    }
 }
@@ -1,16 +1,32 @@
 use position_types::*;
-use {PosError, Square};
+use {BoardError, Piece, PosError, Square, Topology};
 
+mod board_constructive;
 mod board_from_dims;
 mod board_from_pos_iter;
+mod board_from_str;
 mod board_reflect;
+#[cfg(feature = "serde")]
+mod board_serde;
+mod board_symmetry;
 mod board_to_string;
 
+pub use self::board_from_str::{ParseBoardError, ParseBoardErrorKind};
+pub use self::board_symmetry::Symmetry;
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "self::board_serde::BoardData"))]
 pub struct Board {
     width: u32,
     height: u32,
     queens: Vec<PosCoords>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    blocked: Vec<PosCoords>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Piece::is_queen"))]
+    piece: Piece,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Topology::is_flat"))]
+    topology: Topology,
 }
 
 impl Board {
@@ -19,10 +35,14 @@ impl Board {
         let width = 8;
         let height = 8;
         let queens = Vec::new();
+        let blocked = Vec::new();
         Board {
             width,
             height,
             queens,
+            blocked,
+            piece: Piece::Queen,
+            topology: Topology::Flat,
         }
     }
 
@@ -41,6 +61,38 @@ impl Board {
         (self.width, self.height)
     }
 
+    /// Return the number of queens that must be placed on the board to solve
+    /// it. No two queens can share a row or a column, so this is the smaller
+    /// of the board's dimensions. Boards holding other pieces must hold
+    /// `Piece::max_independent` pieces.
+    pub fn target_queens(&self) -> u32 {
+        self.piece.max_independent(self.dims(), self.topology)
+    }
+
+    /// Return the kind of piece placed on the board.
+    pub fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    /// Set the kind of piece placed on the board. The pieces that are already
+    /// on the board are kept in place.
+    pub fn set_piece(&mut self, piece: Piece) {
+        self.piece = piece;
+    }
+
+    /// Return the topology of the board.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Set the topology of the board. On a toroidal board, pieces attack
+    /// across the edges of the board, so fewer kings, knights, and bishops
+    /// can be placed. The target number of queens is unchanged, so some
+    /// toroidal boards cannot be solved.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
     /// Return a bool representing whether or not a position is in bounds.
     pub fn in_bounds(&self, pos: &PosCoords) -> bool {
         pos.0 < self.width && pos.1 < self.height
@@ -53,16 +105,19 @@ impl Board {
         if !self.in_bounds(&pos) {
             return Err(PosError::OutOfBounds);
         } else {
-            match self.queens.contains(&pos) {
-                true => Ok(Square::Queen),
-                false => Ok(Square::Empty),
+            if self.queens.contains(&pos) {
+                Ok(Square::Queen)
+            } else if self.is_blocked(&pos) {
+                Ok(Square::Blocked)
+            } else {
+                Ok(Square::Empty)
             }
         }
     }
 
     /// Add a queen to the board at the given position.
     pub fn add_queen(&mut self, pos: PosCoords) {
-        if self.in_bounds(&pos) {
+        if self.in_bounds(&pos) && !self.is_blocked(&pos) {
             self.queens.push(pos);
             self.queens.sort();
         } else {
@@ -70,17 +125,179 @@ impl Board {
         }
     }
 
+    /// Add a queen to the board at the given position. Returns an error if the
+    /// position is out of bounds, if the square already holds a queen or is
+    /// blocked, or if the board already holds its target number of queens.
+    pub fn try_add_queen(&mut self, pos: PosCoords) -> Result<(), BoardError> {
+        if !self.in_bounds(&pos) {
+            return Err(BoardError::from((PosError::OutOfBounds, pos)));
+        }
+        if self.is_blocked(&pos) {
+            return Err(BoardError::BlockedSquare(pos));
+        }
+        match self.queens.binary_search(&pos) {
+            Ok(_) => Err(BoardError::DuplicateSquare(pos)),
+            Err(_) if self.queens.len() as u32 >= self.target_queens() => {
+                Err(BoardError::ExceedsCapacity(pos))
+            }
+            Err(i) => {
+                self.queens.insert(i, pos);
+                Ok(())
+            }
+        }
+    }
+
+    /// Create a board with the given dimensions, containing the given queens.
+    /// Returns the first error encountered while adding the queens.
+    pub fn try_from_positions<I>(dims: PosCoords, positions: I) -> Result<Board, BoardError>
+    where
+        I: IntoIterator<Item = PosCoords>,
+    {
+        let mut board = Board::from(dims);
+        for pos in positions {
+            board.try_add_queen(pos)?;
+        }
+        Ok(board)
+    }
+
+    /// Create a board with the given dimensions, containing the given queens.
+    /// Repeated positions are only added once. Panics if any of the positions
+    /// are out of bounds.
+    pub fn with_queens<I>(dims: PosCoords, positions: I) -> Board
+    where
+        I: IntoIterator<Item = PosCoords>,
+    {
+        let mut board = Board::from(dims);
+        for pos in positions {
+            if !board.in_bounds(&pos) {
+                panic!("Cannot add queen at position {:?}", pos);
+            }
+            board.queens.push(pos);
+        }
+        board.queens.sort();
+        board.queens.dedup();
+        board
+    }
+
     /// Get a clone of the hash set containing the queen's positions.
     pub fn get_queen_positions(&self) -> Vec<PosCoords> {
         self.queens.clone()
     }
+
+    /// Block the square at the given position. Queens cannot be placed on a
+    /// blocked square, and cannot attack through one. Panics if the position
+    /// is out of bounds, or if the square holds a queen.
+    pub fn add_blocked(&mut self, pos: PosCoords) {
+        if let Err(err) = self.try_add_blocked(pos) {
+            panic!("Cannot block square at position {:?}: {}", pos, err);
+        }
+    }
+
+    /// Block the square at the given position. Returns an error if the
+    /// position is out of bounds, or if the square already holds a queen or
+    /// is already blocked.
+    pub fn try_add_blocked(&mut self, pos: PosCoords) -> Result<(), BoardError> {
+        if !self.in_bounds(&pos) {
+            return Err(BoardError::from((PosError::OutOfBounds, pos)));
+        }
+        if self.queens.contains(&pos) {
+            return Err(BoardError::DuplicateSquare(pos));
+        }
+        match self.blocked.binary_search(&pos) {
+            Ok(_) => Err(BoardError::BlockedSquare(pos)),
+            Err(i) => {
+                self.blocked.insert(i, pos);
+                Ok(())
+            }
+        }
+    }
+
+    /// Create a board with the given dimensions, with the given squares
+    /// blocked. Panics if any of the positions are out of bounds.
+    pub fn with_blocked<I>(dims: PosCoords, positions: I) -> Board
+    where
+        I: IntoIterator<Item = PosCoords>,
+    {
+        let mut board = Board::from(dims);
+        for pos in positions {
+            if !board.in_bounds(&pos) {
+                panic!("Cannot block square at position {:?}", pos);
+            }
+            board.blocked.push(pos);
+        }
+        board.blocked.sort();
+        board.blocked.dedup();
+        board
+    }
+
+    /// Returns true if the square at the given position is blocked.
+    pub fn is_blocked(&self, pos: &PosCoords) -> bool {
+        self.blocked.binary_search(pos).is_ok()
+    }
+
+    /// Return the positions of the blocked squares, in sorted order.
+    pub fn get_blocked_positions(&self) -> Vec<PosCoords> {
+        self.blocked.clone()
+    }
 }
 
 #[cfg(test)]
 mod board_tests {
     use super::Board;
+    use checker::check_board;
     use position_types::*;
-    use Square;
+    use {Piece, Square, Topology};
+
+    #[test]
+    fn try_add_queen_works() {
+        let mut b = Board::new();
+        assert_eq!(b.try_add_queen((3, 4)), Ok(()));
+        assert_eq!(b.try_add_queen((0, 0)), Ok(()));
+        assert_eq!(b.get_queen_positions(), vec![(0, 0), (3, 4)]);
+    }
+
+    #[test]
+    fn try_add_queen_rejects_oob_coords() {
+        let mut b = Board::new();
+        let res = b.try_add_queen((8, 0));
+        assert_eq!(res, Err(BoardError::OutOfBounds((8, 0))));
+        assert!(b.get_queen_positions().is_empty());
+    }
+
+    #[test]
+    fn try_add_queen_rejects_duplicate_squares() {
+        let mut b = Board::new();
+        b.try_add_queen((2, 2)).unwrap();
+        let res = b.try_add_queen((2, 2));
+        assert_eq!(res, Err(BoardError::DuplicateSquare((2, 2))));
+        assert_eq!(b.get_queen_positions(), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn try_add_queen_rejects_queens_beyond_capacity() {
+        let mut b = Board::from((3, 2));
+        b.try_add_queen((0, 0)).unwrap();
+        b.try_add_queen((2, 1)).unwrap();
+        let res = b.try_add_queen((1, 0));
+        assert_eq!(res, Err(BoardError::ExceedsCapacity((1, 0))));
+    }
+
+    #[test]
+    fn try_from_positions_works() {
+        let res = Board::try_from_positions((4, 4), vec![(1, 0), (3, 1)]);
+        let expected = Board::with_queens((4, 4), vec![(1, 0), (3, 1)]);
+        assert_eq!(res, Ok(expected));
+        let res = Board::try_from_positions((4, 4), vec![(1, 0), (4, 1)]);
+        assert_eq!(res, Err(BoardError::OutOfBounds((4, 1))));
+        let res = Board::try_from_positions((4, 4), vec![(1, 0), (1, 0)]);
+        assert_eq!(res.unwrap_err().pos(), (1, 0));
+    }
+
+    #[test]
+    fn board_errors_can_be_displayed() {
+        let err = BoardError::OutOfBounds((8, 0));
+        assert_eq!(err.to_string(), "position (8, 0) is out of bounds");
+    }
 
     /// Test that the dimensions of a default board are correct.
     #[test]
@@ -106,6 +323,96 @@ mod board_tests {
         assert_eq!(s, Ok(Square::Queen));
     }
 
+    #[test]
+    fn blocked_squares_are_added() {
+        let mut b = Board::from((4, 4));
+        assert_eq!(b.try_add_blocked((2, 1)), Ok(()));
+        b.add_blocked((0, 3));
+        assert_eq!(b.get_blocked_positions(), vec![(0, 3), (2, 1)]);
+        assert!(b.is_blocked(&(2, 1)));
+        assert!(!b.is_blocked(&(1, 2)));
+        assert_eq!(b.get_square(1, 2), Ok(Square::Blocked));
+        assert_eq!(b.get_square(2, 1), Ok(Square::Empty));
+        assert_eq!(Board::with_blocked((4, 4), vec![(2, 1), (0, 3)]), b);
+    }
+
+    #[test]
+    fn queens_cannot_share_a_blocked_square() {
+        let mut b = Board::with_blocked((4, 4), vec![(1, 1)]);
+        let res = b.try_add_queen((1, 1));
+        assert_eq!(res, Err(BoardError::BlockedSquare((1, 1))));
+        b.try_add_queen((2, 2)).unwrap();
+        let res = b.try_add_blocked((2, 2));
+        assert_eq!(res, Err(BoardError::DuplicateSquare((2, 2))));
+        let res = b.try_add_blocked((1, 1));
+        assert_eq!(res, Err(BoardError::BlockedSquare((1, 1))));
+        let res = b.try_add_blocked((4, 1));
+        assert_eq!(res, Err(BoardError::OutOfBounds((4, 1))));
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_queen_panics_for_blocked_squares() {
+        let mut b = Board::with_blocked((4, 4), vec![(1, 1)]);
+        b.add_queen((1, 1));
+    }
+
+    #[test]
+    fn target_depends_on_the_piece() {
+        let mut b = Board::from((8, 8));
+        assert_eq!(b.piece(), Piece::Queen);
+        assert_eq!(b.target_queens(), 8);
+        b.set_piece(Piece::Knight);
+        assert_eq!(b.piece(), Piece::Knight);
+        assert_eq!(b.target_queens(), 32);
+    }
+
+    #[test]
+    fn boards_are_flat_by_default() {
+        let mut b = Board::from((5, 5));
+        assert_eq!(b.topology(), Topology::Flat);
+        b.set_topology(Topology::Toroidal);
+        assert_eq!(b.topology(), Topology::Toroidal);
+        assert_eq!(b.target_queens(), 5);
+        assert_ne!(b, Board::from((5, 5)));
+    }
+
+    #[test]
+    fn target_depends_on_the_topology() {
+        let mut b = Board::from((5, 5));
+        b.set_piece(Piece::King);
+        assert_eq!(b.target_queens(), 9);
+        b.set_topology(Topology::Toroidal);
+        assert_eq!(b.target_queens(), 5);
+        for &pos in [(0, 0), (2, 1), (4, 2), (1, 3), (3, 4)].iter() {
+            b.try_add_queen(pos).unwrap();
+        }
+        assert!(check_board(&b).is_solved);
+        let res = b.try_add_queen((0, 2));
+        assert_eq!(res, Err(BoardError::ExceedsCapacity((0, 2))));
+    }
+
+    /// Test that boards with other dimensions can be created with queens.
+    #[test]
+    fn board_with_queens_keeps_dimensions() {
+        let b = Board::with_queens((5, 3), vec![(4, 2), (0, 0)]);
+        assert_eq!(b.dims(), (5, 3));
+        assert_eq!(b.target_queens(), 3);
+        assert_eq!(b.get_queen_positions(), vec![(0, 0), (4, 2)]);
+    }
+
+    #[test]
+    fn board_with_queens_merges_repeated_positions() {
+        let b = Board::with_queens((4, 4), vec![(1, 2), (0, 0), (1, 2)]);
+        assert_eq!(b.get_queen_positions(), vec![(0, 0), (1, 2)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn board_with_queens_panics_for_oob_coords() {
+        Board::with_queens((5, 3), vec![(0, 3)]);
+    }
+
     /// Test that `get_square` will fail gracefully given a coordinate pair
     /// that is out of bounds of the board.
     #[test]
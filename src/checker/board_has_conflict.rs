@@ -1,37 +1,190 @@
-use queen::get_queen_move_sets;
-use {Board, CoordList, PosCoords};
+use piece::Line;
+use {Board, CoordList, Piece, PosCoords, Topology};
+
+/// The number of queens in each segment of a family of parallel lines, such
+/// as the columns of a board. Blocked squares split a line into segments,
+/// and two queens are only in conflict if they share a segment.
+struct Segments {
+    /// The blocked squares on these lines, as sorted pairs of the index of
+    /// the line and the offset of the square along it.
+    blocked: Vec<(usize, u32)>,
+    counts: Vec<u32>,
+}
+
+impl Segments {
+    fn new(num_lines: usize, mut blocked: Vec<(usize, u32)>) -> Segments {
+        blocked.sort();
+        Segments {
+            counts: vec![0; num_lines + blocked.len()],
+            blocked,
+        }
+    }
+
+    /// Return the index of the segment containing the given offset along a
+    /// line. Segments are numbered by line, and then by their position along
+    /// it, so this is the line index plus the number of blocked squares
+    /// before the position. Without blocked squares, this is the line index.
+    fn get_segment(&self, (line, offset): (usize, u32)) -> usize {
+        line + self.blocked.partition_point(|&square| square < (line, offset))
+    }
+}
+
+/// The number of pieces in each line that the board's piece slides along,
+/// such as the columns, rows, diagonals and anti-diagonals for queens. Two
+/// pieces are in conflict if they share one of these lines, or if one can
+/// leap to the other, so conflicts can be found without collecting each
+/// piece's moves. Lines wrap around into cycles on a toroidal board, so
+/// they are not counted, and each pair of pieces is checked instead.
+struct LineCounts<'a> {
+    dims: PosCoords,
+    piece: Piece,
+    topology: Topology,
+    pieces: &'a [PosCoords],
+    blocked: CoordList,
+    lines: Vec<(Line, Segments)>,
+}
+
+impl<'a> LineCounts<'a> {
+    /// Count the pieces in each line. `pieces` must be sorted.
+    fn new(board: &Board, pieces: &'a [PosCoords]) -> LineCounts<'a> {
+        let dims = board.dims();
+        let blocked = board.get_blocked_positions();
+        let topology = board.topology();
+        let lines = match topology {
+            Topology::Flat => board.piece().lines(),
+            Topology::Toroidal => &[],
+        };
+        let mut lines = lines
+            .iter()
+            .map(|&line| {
+                let blocked = blocked.iter().map(|&pos| line.locate(pos, dims)).collect();
+                (line, Segments::new(line.num_lines(dims), blocked))
+            })
+            .collect::<Vec<(Line, Segments)>>();
+        for &pos in pieces.iter() {
+            for &mut (line, ref mut segments) in lines.iter_mut() {
+                let segment = segments.get_segment(line.locate(pos, dims));
+                segments.counts[segment] += 1;
+            }
+        }
+        LineCounts {
+            dims,
+            piece: board.piece(),
+            topology,
+            pieces,
+            blocked,
+            lines,
+        }
+    }
+
+    /// Returns true if a piece at the given position shares a line with
+    /// another piece, or can leap to one.
+    fn in_conflict(&self, pos: PosCoords) -> bool {
+        if self.topology == Topology::Toroidal {
+            let attacks = |other| {
+                self.piece
+                    .attacks(pos, other, self.dims, &self.blocked, self.topology)
+            };
+            return self.pieces.iter().any(|&other| other != pos && attacks(other));
+        }
+        let shares_line = self.lines.iter().any(|&(line, ref segments)| {
+            segments.counts[segments.get_segment(line.locate(pos, self.dims))] > 1
+        });
+        shares_line || self.can_leap_to_piece(pos)
+    }
+
+    /// Returns true if a piece at the given position can leap to another.
+    fn can_leap_to_piece(&self, pos: PosCoords) -> bool {
+        self.piece.leaps().iter().any(|&(dx, dy)| {
+            let (x, y) = (pos.0 as i64 + dx, pos.1 as i64 + dy);
+            x >= 0 && y >= 0 && self.pieces.binary_search(&(x as u32, y as u32)).is_ok()
+        })
+    }
+}
 
 /// Check if the board has any conflicted queens.
 pub fn board_has_conflict(board: &Board) -> bool {
-    let dims: PosCoords = board.dims();
     let queens: Vec<PosCoords> = board.get_queen_positions();
-    let move_sets = get_queen_move_sets(queens.clone(), dims);
-    for queen_in_conflict in queens
-        .into_iter()
-        .map(|pos| pos_in_conflict(pos, &move_sets))
-    {
-        if queen_in_conflict {
-            return true;
-        };
-    }
-    false
+    let counts = LineCounts::new(board, &queens);
+    queens.iter().any(|&pos| counts.in_conflict(pos))
 }
 
-/// Returns a boolean value representing whether a position is in more than
-/// one queen's movement space. Used to check if queens are in conflict.
-fn pos_in_conflict(pos: PosCoords, move_sets: &[CoordList]) -> bool {
-    let num_queens_contesting_space = move_sets
+/// Return the positions of the queens that are in conflict with another queen.
+pub fn get_conflicting_queens(board: &Board) -> CoordList {
+    let queens: Vec<PosCoords> = board.get_queen_positions();
+    let counts = LineCounts::new(board, &queens);
+    queens
         .iter()
-        .map(|movements| movements.contains(&pos))
-        .filter(|&in_space| in_space == true)
-        .count();
-    num_queens_contesting_space > 1
+        .cloned()
+        .filter(|&pos| counts.in_conflict(pos))
+        .collect()
 }
 
 #[cfg(test)]
 mod has_conflict_tests {
-    use super::board_has_conflict;
-    use Board;
+    use super::{board_has_conflict, get_conflicting_queens};
+    use {Board, Piece, Topology};
+
+    #[test]
+    fn conflicting_queens_are_found() {
+        let b: Board = [(0, 0), (1, 0), (7, 2)].iter().cloned().collect();
+        assert_eq!(get_conflicting_queens(&b), vec![(0, 0), (1, 0)]);
+        let b: Board = [(0, 0), (7, 2)].iter().cloned().collect();
+        assert!(get_conflicting_queens(&b).is_empty());
+    }
+
+    #[test]
+    fn conflicts_are_found_on_non_square_boards() {
+        let b = Board::with_queens((5, 2), vec![(0, 1), (1, 0), (4, 0)]);
+        assert_eq!(get_conflicting_queens(&b), vec![(0, 1), (1, 0), (4, 0)]);
+        let b = Board::with_queens((2, 5), vec![(0, 4), (1, 0)]);
+        assert!(!board_has_conflict(&b));
+        let b = Board::with_queens((2, 5), vec![(0, 4), (1, 3)]);
+        assert!(board_has_conflict(&b));
+    }
+
+    #[test]
+    fn blocked_squares_separate_queens() {
+        let mut b = Board::with_blocked((4, 4), vec![(1, 0), (1, 1), (3, 2)]);
+        [(0, 0), (2, 0), (0, 2), (2, 2)].iter().for_each(|&pos| b.add_queen(pos));
+        assert_eq!(get_conflicting_queens(&b), vec![(0, 0), (0, 2), (2, 0), (2, 2)]);
+        let mut b = Board::with_blocked((4, 4), vec![(1, 0), (1, 1), (0, 1), (2, 1)]);
+        [(0, 0), (2, 0), (0, 2), (2, 2)].iter().for_each(|&pos| b.add_queen(pos));
+        assert_eq!(get_conflicting_queens(&b), vec![(0, 2), (2, 2)]);
+        b.add_blocked((1, 2));
+        assert!(!board_has_conflict(&b));
+    }
+
+    #[test]
+    fn conflicts_depend_on_the_piece() {
+        let mut b = Board::with_queens((4, 4), vec![(0, 0), (1, 2), (3, 3)]);
+        assert_eq!(get_conflicting_queens(&b), vec![(0, 0), (3, 3)]);
+        b.set_piece(Piece::Rook);
+        assert!(!board_has_conflict(&b));
+        b.set_piece(Piece::Bishop);
+        assert_eq!(get_conflicting_queens(&b), vec![(0, 0), (3, 3)]);
+        b.set_piece(Piece::Knight);
+        assert_eq!(get_conflicting_queens(&b), vec![(0, 0), (1, 2), (3, 3)]);
+        b.set_piece(Piece::King);
+        assert!(!board_has_conflict(&b));
+        b.add_queen((2, 2));
+        assert_eq!(get_conflicting_queens(&b), vec![(1, 2), (2, 2), (3, 3)]);
+    }
+
+    /// The queens at (0, 0) and (4, 1) share a wrapped anti-diagonal, and the
+    /// queens at (1, 3) and (4, 1) share a wrapped diagonal.
+    #[test]
+    fn toroidal_diagonals_wrap_around() {
+        let mut b = Board::with_queens((5, 5), vec![(0, 0), (1, 3), (4, 1)]);
+        assert!(!board_has_conflict(&b));
+        b.set_topology(Topology::Toroidal);
+        assert_eq!(get_conflicting_queens(&b), vec![(0, 0), (1, 3), (4, 1)]);
+        let b = Board::with_queens((5, 5), vec![(0, 0), (1, 2), (2, 4), (3, 1), (4, 3)]);
+        let mut torus = b.clone();
+        torus.set_topology(Topology::Toroidal);
+        assert!(!board_has_conflict(&b));
+        assert!(!board_has_conflict(&torus));
+    }
 
     #[test]
     fn default_board_is_not_a_solution_has_no_conflict() {
@@ -1,10 +1,11 @@
 /// Represents a given solution state.
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CheckResult {
     pub has_conflict: bool,
     pub is_solved: bool,
-    pub num_queens: u8,
-    pub num_free_spaces: u8,
+    pub num_queens: u32,
+    pub num_free_spaces: u64,
 }
 
 #[cfg(test)]
@@ -12,6 +13,9 @@ mod check_result_ordering_test {
     extern crate rand;
     use super::CheckResult;
     use rand::Rng;
+    use random::seeded_rng;
+
+    const RNG_SEED: u64 = 3;
 
     #[test]
     fn solution_is_gt_than_default() {
@@ -29,7 +33,7 @@ mod check_result_ordering_test {
 
     #[test]
     fn sort_various_results() {
-        let mut rng = rand::thread_rng();
+        let mut rng = seeded_rng(RNG_SEED);
         let num_trials = 100;
         let mut input = vec![
             create_default_board_check_result(),
@@ -91,3 +95,24 @@ mod check_result_ordering_test {
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod check_result_serde_tests {
+    use super::CheckResult;
+    use serde_json;
+
+    #[test]
+    fn check_result_round_trip_works() {
+        let res = CheckResult {
+            has_conflict: false,
+            is_solved: true,
+            num_queens: 8,
+            num_free_spaces: 56,
+        };
+        let json = serde_json::to_string(&res).unwrap();
+        let expected =
+            r#"{"has_conflict":false,"is_solved":true,"num_queens":8,"num_free_spaces":56}"#;
+        assert_eq!(json, expected);
+        assert_eq!(serde_json::from_str::<CheckResult>(&json).unwrap(), res);
+    }
+}
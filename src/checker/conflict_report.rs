@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use piece::Line;
+use topology::gcd;
+use {Board, CoordList, PosCoords, Topology};
+
+/// The line along which two pieces attack each other, or a leap for pieces
+/// such as knights.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AttackDirection {
+    /// The queens share a row.
+    Row,
+    /// The queens share a column.
+    Column,
+    /// The queens share a diagonal running from the bottom-left corner
+    /// towards the top-right corner, where `x - y` is constant.
+    Diagonal,
+    /// The queens share a diagonal running from the top-left corner towards
+    /// the bottom-right corner, where `x + y` is constant.
+    AntiDiagonal,
+    /// One piece can leap to the other, as a knight or a king.
+    Leap,
+}
+
+/// A pair of queens that attack each other. The positions are sorted, so
+/// that `a` is less than `b`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Attack {
+    pub a: PosCoords,
+    pub b: PosCoords,
+    pub direction: AttackDirection,
+}
+
+/// A detailed report of the conflicts on a board, returned by `analyze_board`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConflictReport {
+    /// Each pair of queens that attack each other, in sorted order.
+    pub attacks: Vec<Attack>,
+    /// The number of queens attacking each queen, sorted by position. This
+    /// includes the queens that are not attacked.
+    pub conflict_counts: Vec<(PosCoords, u32)>,
+}
+
+impl ConflictReport {
+    /// Returns true if any queens attack each other.
+    pub fn has_conflict(&self) -> bool {
+        !self.attacks.is_empty()
+    }
+
+    /// Return the number of queens attacking the queen at a position, or 0 if
+    /// there is no queen there.
+    pub fn conflicts_for(&self, pos: PosCoords) -> u32 {
+        match self
+            .conflict_counts
+            .binary_search_by_key(&pos, |&(queen, _)| queen)
+        {
+            Ok(i) => self.conflict_counts[i].1,
+            Err(_) => 0,
+        }
+    }
+
+    /// Return the positions of the queens that are attacked by another queen.
+    pub fn conflicting_queens(&self) -> CoordList {
+        self.conflict_counts
+            .iter()
+            .filter(|&&(_, count)| count > 0)
+            .map(|&(pos, _)| pos)
+            .collect()
+    }
+}
+
+/// Find every pair of queens on the board that attack each other. A queen
+/// only attacks the nearest queen in each direction, so three queens in the
+/// same row form two attacking pairs, and a queen is attacked by at most
+/// eight others. Blocked squares also stop a queen's attacks. A queen is
+/// attacked if, and only if, `check_board` would consider it to be in
+/// conflict. Pieces other than queens only attack along their own lines,
+/// and pieces that leap attack every piece they can leap to. Lines wrap
+/// around into cycles on a toroidal board, so every pair of pieces that
+/// attack each other is reported instead.
+pub fn analyze_board(board: &Board) -> ConflictReport {
+    let queens = board.get_queen_positions();
+    let mut attacks = match board.topology() {
+        Topology::Flat => get_nearest_attacks(board, &queens),
+        Topology::Toroidal => get_wrapped_attacks(board, &queens),
+    };
+    attacks.sort();
+    let mut conflict_counts = queens
+        .into_iter()
+        .map(|pos| (pos, 0))
+        .collect::<Vec<(PosCoords, u32)>>();
+    for attack in attacks.iter() {
+        for pos in [attack.a, attack.b].iter() {
+            if let Ok(i) = conflict_counts.binary_search_by_key(pos, |&(queen, _)| queen) {
+                conflict_counts[i].1 += 1;
+            }
+        }
+    }
+    ConflictReport {
+        attacks,
+        conflict_counts,
+    }
+}
+
+/// Find the attacks between the nearest pair of queens along each line of a
+/// flat board, along with the attacks made by leaping.
+fn get_nearest_attacks(board: &Board, queens: &[PosCoords]) -> Vec<Attack> {
+    let piece = board.piece();
+    let mut squares = queens
+        .iter()
+        .map(|&pos| (pos, false))
+        .chain(board.get_blocked_positions().into_iter().map(|pos| (pos, true)))
+        .collect::<Vec<(PosCoords, bool)>>();
+    // Squares are sorted by their x coordinate, and then their y coordinate,
+    // so each line's squares are pushed in the order they appear along it.
+    squares.sort();
+    let mut lines: HashMap<(AttackDirection, i64), Vec<(PosCoords, bool)>> = HashMap::new();
+    for &((x, y), is_blocked) in squares.iter() {
+        let (x_i, y_i) = (x as i64, y as i64);
+        let keys = [
+            (Line::Row, AttackDirection::Row, y_i),
+            (Line::Column, AttackDirection::Column, x_i),
+            (Line::Diagonal, AttackDirection::Diagonal, x_i - y_i),
+            (Line::AntiDiagonal, AttackDirection::AntiDiagonal, x_i + y_i),
+        ];
+        for &(line, direction, index) in keys.iter() {
+            if piece.lines().contains(&line) {
+                let squares = lines.entry((direction, index)).or_default();
+                squares.push(((x, y), is_blocked));
+            }
+        }
+    }
+    let mut attacks = lines
+        .into_iter()
+        .flat_map(|((direction, _), line)| {
+            line.windows(2)
+                .filter(|pair| !pair[0].1 && !pair[1].1)
+                .map(|pair| Attack {
+                    a: pair[0].0,
+                    b: pair[1].0,
+                    direction,
+                })
+                .collect::<Vec<Attack>>()
+        })
+        .collect::<Vec<Attack>>();
+    for &a in queens.iter() {
+        for &(dx, dy) in piece.leaps().iter() {
+            let (x, y) = (a.0 as i64 + dx, a.1 as i64 + dy);
+            let b = (x as u32, y as u32);
+            if x >= 0 && y >= 0 && b > a && queens.binary_search(&b).is_ok() {
+                let direction = AttackDirection::Leap;
+                attacks.push(Attack { a, b, direction });
+            }
+        }
+    }
+    attacks
+}
+
+/// Find every pair of queens on a toroidal board that attack each other.
+/// Each attack is reported along the first of the piece's lines that joins
+/// the pair, or as a leap.
+fn get_wrapped_attacks(board: &Board, queens: &[PosCoords]) -> Vec<Attack> {
+    let (piece, dims) = (board.piece(), board.dims());
+    let blocked = board.get_blocked_positions();
+    let topology = Topology::Toroidal;
+    let period = gcd(dims.0, dims.1) as i64;
+    let mut attacks = Vec::new();
+    for (i, &a) in queens.iter().enumerate() {
+        for &b in queens[i + 1..].iter() {
+            if !piece.attacks(a, b, dims, &blocked, topology) {
+                continue;
+            }
+            let offset = topology.offset(a, b, dims);
+            let line = [Line::Row, Line::Column, Line::Diagonal, Line::AntiDiagonal]
+                .iter()
+                .cloned()
+                .filter(|line| piece.lines().contains(line))
+                .find(|line| line.wraps_between(offset, period));
+            let direction = match line {
+                Some(Line::Row) => AttackDirection::Row,
+                Some(Line::Column) => AttackDirection::Column,
+                Some(Line::Diagonal) => AttackDirection::Diagonal,
+                Some(Line::AntiDiagonal) => AttackDirection::AntiDiagonal,
+                None => AttackDirection::Leap,
+            };
+            attacks.push(Attack { a, b, direction });
+        }
+    }
+    attacks
+}
+
+#[cfg(test)]
+mod conflict_report_tests {
+    use super::{analyze_board, Attack, AttackDirection};
+    use checker::get_conflicting_queens;
+    use random::RandomBoard;
+    use {Board, Piece, Topology};
+
+    fn attack(a: (u32, u32), b: (u32, u32), direction: AttackDirection) -> Attack {
+        Attack { a, b, direction }
+    }
+
+    #[test]
+    fn valid_solution_has_no_attacks() {
+        let b = Board::constructive_solution(8).unwrap();
+        let report = analyze_board(&b);
+        assert!(!report.has_conflict());
+        assert_eq!(report.conflict_counts.len(), 8);
+        assert!(report.conflict_counts.iter().all(|&(_, count)| count == 0));
+    }
+
+    ///   0123
+    ///   ----
+    /// 3|   Q|
+    /// 2|    |
+    /// 1| Q  |
+    /// 0|Q Q |
+    ///   ----
+    #[test]
+    fn attacks_report_their_direction() {
+        let b = Board::with_queens((4, 4), vec![(0, 0), (2, 0), (1, 1), (3, 3)]);
+        let report = analyze_board(&b);
+        let expected = vec![
+            attack((0, 0), (1, 1), AttackDirection::Diagonal),
+            attack((0, 0), (2, 0), AttackDirection::Row),
+            attack((1, 1), (2, 0), AttackDirection::AntiDiagonal),
+            attack((1, 1), (3, 3), AttackDirection::Diagonal),
+        ];
+        assert_eq!(report.attacks, expected);
+        assert_eq!(report.conflicts_for((1, 1)), 3);
+        assert_eq!(report.conflicts_for((0, 0)), 2);
+        assert_eq!(report.conflicts_for((3, 3)), 1);
+        assert_eq!(report.conflicts_for((3, 0)), 0);
+    }
+
+    #[test]
+    fn queens_only_attack_the_nearest_queen_in_a_line() {
+        let b = Board::with_queens((8, 8), vec![(0, 5), (3, 5), (7, 5)]);
+        let report = analyze_board(&b);
+        let expected = vec![
+            attack((0, 5), (3, 5), AttackDirection::Row),
+            attack((3, 5), (7, 5), AttackDirection::Row),
+        ];
+        assert_eq!(report.attacks, expected);
+        assert_eq!(report.conflicts_for((3, 5)), 2);
+    }
+
+    /// The queens at (3, 5) and (7, 5) are separated by a blocked square.
+    #[test]
+    fn blocked_squares_stop_attacks() {
+        let mut b = Board::with_blocked((8, 8), vec![(4, 5)]);
+        [(0, 5), (3, 5), (7, 5)].iter().for_each(|&pos| b.add_queen(pos));
+        let report = analyze_board(&b);
+        let expected = vec![attack((0, 5), (3, 5), AttackDirection::Row)];
+        assert_eq!(report.attacks, expected);
+        assert_eq!(report.conflicts_for((7, 5)), 0);
+    }
+
+    #[test]
+    fn pieces_attack_along_their_own_lines() {
+        let mut b = Board::with_queens((4, 4), vec![(0, 0), (2, 0), (1, 1), (3, 3)]);
+        b.set_piece(Piece::Rook);
+        let expected = vec![attack((0, 0), (2, 0), AttackDirection::Row)];
+        assert_eq!(analyze_board(&b).attacks, expected);
+        b.set_piece(Piece::King);
+        let expected = vec![
+            attack((0, 0), (1, 1), AttackDirection::Leap),
+            attack((1, 1), (2, 0), AttackDirection::Leap),
+        ];
+        assert_eq!(analyze_board(&b).attacks, expected);
+        b.set_piece(Piece::Knight);
+        assert!(!analyze_board(&b).has_conflict());
+    }
+
+    #[test]
+    fn toroidal_attacks_wrap_around() {
+        let mut b = Board::with_queens((5, 5), vec![(0, 0), (1, 3), (4, 1), (4, 4)]);
+        b.set_topology(Topology::Toroidal);
+        let report = analyze_board(&b);
+        let expected = vec![
+            attack((0, 0), (4, 1), AttackDirection::AntiDiagonal),
+            attack((0, 0), (4, 4), AttackDirection::Diagonal),
+            attack((1, 3), (4, 1), AttackDirection::Diagonal),
+            attack((4, 1), (4, 4), AttackDirection::Column),
+        ];
+        assert_eq!(report.attacks, expected);
+        assert_eq!(report.conflicting_queens(), get_conflicting_queens(&b));
+    }
+
+    #[test]
+    fn column_attacks_are_found() {
+        let b = Board::with_queens((3, 6), vec![(1, 0), (1, 5)]);
+        let report = analyze_board(&b);
+        let expected = vec![attack((1, 0), (1, 5), AttackDirection::Column)];
+        assert_eq!(report.attacks, expected);
+    }
+
+    #[test]
+    fn conflicting_queens_match_checker() {
+        let mut generator = RandomBoard::from_seed((8, 8), 20);
+        for k in 0..12 {
+            let mut b = generator.arbitrary(k).unwrap();
+            for &piece in Piece::all() {
+                for &topology in [Topology::Flat, Topology::Toroidal].iter() {
+                    b.set_piece(piece);
+                    b.set_topology(topology);
+                    let report = analyze_board(&b);
+                    assert_eq!(report.conflicting_queens(), get_conflicting_queens(&b));
+                }
+            }
+        }
+        let mut b = generator.arbitrary(10).unwrap();
+        for &pos in [(1, 1), (3, 4), (6, 2), (5, 5), (2, 6)].iter() {
+            if b.try_add_blocked(pos).is_ok() {
+                let report = analyze_board(&b);
+                assert_eq!(report.conflicting_queens(), get_conflicting_queens(&b));
+            }
+        }
+    }
+}
@@ -1,19 +1,26 @@
 mod board_has_conflict;
 mod check_result;
+mod conflict_report;
 
 use self::board_has_conflict::board_has_conflict;
+pub use self::board_has_conflict::get_conflicting_queens;
 pub use self::check_result::CheckResult;
+pub use self::conflict_report::{analyze_board, Attack, AttackDirection, ConflictReport};
 
 use Board;
 
 /// Check the state of the board. Returns a `CheckResult` object, containing
 /// information about whether the given positions contain a conflict, whether
-/// the given positions represent a solution to the eight problem, etc.
+/// the given positions represent a solution to the eight problem, etc. A
+/// board is solved once it holds `Board::target_queens` queens without any
+/// conflicts. Queens separated by a blocked square are not in conflict, and
+/// blocked squares are not counted as free spaces.
 pub fn check_board(board: &Board) -> CheckResult {
-    let num_queens = board.get_queen_positions().len() as u8;
-    let num_free_spaces = get_total_number_of_squares(&board) - num_queens;
-    let has_conflict = board_has_conflict(&board);
-    let is_solved = num_queens == 8 && !has_conflict;
+    let num_queens = board.get_queen_positions().len() as u32;
+    let num_blocked = board.get_blocked_positions().len() as u64;
+    let num_free_spaces = get_total_number_of_squares(board) - num_queens as u64 - num_blocked;
+    let has_conflict = board_has_conflict(board);
+    let is_solved = num_queens == board.target_queens() && !has_conflict;
     CheckResult {
         num_queens,
         num_free_spaces,
@@ -22,9 +29,9 @@ pub fn check_board(board: &Board) -> CheckResult {
     }
 }
 
-fn get_total_number_of_squares(b: &Board) -> u8 {
+fn get_total_number_of_squares(b: &Board) -> u64 {
     let (width, height) = b.dims();
-    (width * height) as u8
+    width as u64 * height as u64
 }
 
 #[cfg(test)]
@@ -163,4 +170,56 @@ mod check_result_tests {
         };
         assert_eq!(check_res, expected);
     }
+
+    #[test]
+    fn non_square_board_is_solved_with_fewer_queens() {
+        let b = Board::with_queens((4, 2), vec![(0, 0), (2, 1)]);
+        let check_res = check_board(&b);
+        let expected = CheckResult {
+            is_solved: true,
+            has_conflict: false,
+            num_queens: 2,
+            num_free_spaces: 6,
+        };
+        assert_eq!(check_res, expected);
+    }
+
+    ///   0123
+    ///   ----
+    /// 3|    |
+    /// 2|  Q |
+    /// 1|    |
+    /// 0|Q#Q |
+    ///   ----
+    #[test]
+    fn blocked_squares_are_respected() {
+        let mut b = Board::with_blocked((4, 4), vec![(1, 0)]);
+        [(0, 0), (2, 0), (2, 2)].iter().for_each(|&pos| b.add_queen(pos));
+        let check_res = check_board(&b);
+        let expected = CheckResult {
+            is_solved: false,
+            has_conflict: true,
+            num_queens: 3,
+            num_free_spaces: 12,
+        };
+        assert_eq!(check_res, expected);
+        b.add_blocked((2, 1));
+        b.add_blocked((1, 1));
+        let check_res = check_board(&b);
+        assert!(!check_res.has_conflict);
+        assert_eq!(check_res.num_free_spaces, 10);
+    }
+
+    #[test]
+    fn large_board_counts_free_spaces() {
+        let b = Board::with_queens((32, 32), vec![(0, 0), (2, 1), (4, 2)]);
+        let check_res = check_board(&b);
+        let expected = CheckResult {
+            is_solved: false,
+            has_conflict: false,
+            num_queens: 3,
+            num_free_spaces: 1021,
+        };
+        assert_eq!(check_res, expected);
+    }
 }
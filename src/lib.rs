@@ -1,15 +1,29 @@
 #![feature(iterator_flatten, test, try_from)]
 
 extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 pub mod board;
 pub mod checker;
+pub mod permutation;
+pub mod piece;
 pub mod position;
 pub mod queen;
+pub mod random;
+pub mod render;
 pub mod solver;
+pub mod topology;
 
-pub use board::Board;
+pub use board::{Board, Symmetry};
+pub use piece::Piece;
 pub use position::position_types;
+pub use topology::Topology;
 use position::position_types::*;
 
 /// Square type, this represent whether or not a square is occupied.
@@ -17,4 +31,7 @@ use position::position_types::*;
 pub enum Square {
     Empty = 0,
     Queen = 1,
+    /// A hole in the board. Queens cannot be placed on a blocked square, and
+    /// cannot attack through one.
+    Blocked = 2,
 }
@@ -0,0 +1,231 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use {Board, CoordList, PosCoords};
+
+mod permutation_rank;
+
+pub use self::permutation_rank::MAX_RANKED_SIZE;
+
+/// A board with exactly one queen in each row and column, such as a solution
+/// to the n-queens problem, represented as a permutation. The value at index
+/// `x` is the row of the queen in column `x`.
+///
+/// Permutations are printed and parsed in one-line notation, listing the
+/// rows starting from 1, e.g. "15863724". Boards larger than 9x9 separate the
+/// rows with spaces, e.g. "1 3 5 2 10 12 14 4 13 9 6 8 11 7".
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct Permutation {
+    rows: Vec<u32>,
+}
+
+/// Permutation errors. Returned if a board or string does not represent a
+/// valid permutation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PermutationError {
+    /// The board is not square, so it cannot be represented.
+    NotSquare(PosCoords),
+    /// The rows do not contain each row of the board exactly once.
+    NotPermutation,
+    /// A row in the string could not be parsed.
+    InvalidRow(String),
+}
+
+impl Permutation {
+    /// Create a permutation from the row of the queen in each column, where
+    /// the rows are counted from 0. Returns an error if each row does not
+    /// appear exactly once.
+    pub fn from_rows(rows: Vec<u32>) -> Result<Permutation, PermutationError> {
+        let n = rows.len();
+        let mut seen = vec![false; n];
+        for &row in rows.iter() {
+            match seen.get_mut(row as usize) {
+                Some(seen) if !*seen => *seen = true,
+                _ => return Err(PermutationError::NotPermutation),
+            }
+        }
+        Ok(Permutation { rows })
+    }
+
+    /// Create a permutation from a list of queen positions on an n x n board.
+    pub fn from_coords(queens: &[PosCoords], n: u32) -> Result<Permutation, PermutationError> {
+        let mut rows = vec![n; n as usize];
+        for &(x, y) in queens.iter() {
+            match rows.get_mut(x as usize) {
+                Some(row) if *row == n && y < n => *row = y,
+                _ => return Err(PermutationError::NotPermutation),
+            }
+        }
+        Permutation::from_rows(rows)
+    }
+
+    /// Return the size of the board that this permutation represents.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns true if the permutation is empty.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Return the row of the queen in each column, counted from 0.
+    pub fn rows(&self) -> &[u32] {
+        &self.rows
+    }
+
+    /// Return the queen positions, in sorted order.
+    pub fn to_coords(&self) -> CoordList {
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(x, &y)| (x as u32, y))
+            .collect()
+    }
+
+    /// Return a square board containing the queens.
+    pub fn to_board(&self) -> Board {
+        let n = self.len() as u32;
+        Board::with_queens((n, n), self.to_coords())
+    }
+}
+
+impl<'a> TryFrom<&'a Board> for Permutation {
+    type Error = PermutationError;
+
+    fn try_from(board: &'a Board) -> Result<Permutation, PermutationError> {
+        let (width, height) = board.dims();
+        if width != height {
+            return Err(PermutationError::NotSquare((width, height)));
+        }
+        Permutation::from_coords(&board.get_queen_positions(), width)
+    }
+}
+
+impl fmt::Display for Permutation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| (row + 1).to_string())
+            .collect::<Vec<String>>();
+        match self.len() {
+            0..=9 => write!(f, "{}", rows.concat()),
+            _ => write!(f, "{}", rows.join(" ")),
+        }
+    }
+}
+
+impl FromStr for Permutation {
+    type Err = PermutationError;
+
+    /// Parse a permutation in one-line notation. Rows are read as single
+    /// digits, unless the string contains whitespace.
+    fn from_str(s: &str) -> Result<Permutation, PermutationError> {
+        let s = s.trim();
+        let entries = match s.contains(char::is_whitespace) {
+            true => s.split_whitespace().collect::<Vec<&str>>(),
+            false => s
+                .char_indices()
+                .map(|(i, c)| &s[i..i + c.len_utf8()])
+                .collect(),
+        };
+        if entries.is_empty() {
+            return Err(PermutationError::InvalidRow(String::new()));
+        }
+        let rows = entries
+            .into_iter()
+            .map(|entry| match entry.parse::<u32>() {
+                Ok(row) if row > 0 => Ok(row - 1),
+                _ => Err(PermutationError::InvalidRow(String::from(entry))),
+            })
+            .collect::<Result<Vec<u32>, PermutationError>>()?;
+        Permutation::from_rows(rows)
+    }
+}
+
+impl fmt::Display for PermutationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PermutationError::NotSquare(dims) => {
+                write!(f, "a {}x{} board is not square", dims.0, dims.1)
+            }
+            PermutationError::NotPermutation => {
+                write!(f, "each row and column must hold exactly one queen")
+            }
+            PermutationError::InvalidRow(ref row) => write!(f, "invalid row '{}'", row),
+        }
+    }
+}
+
+impl Error for PermutationError {}
+
+#[cfg(test)]
+mod permutation_tests {
+    use super::{Permutation, PermutationError};
+    use std::convert::TryFrom;
+    use Board;
+
+    #[test]
+    fn permutation_is_displayed_in_one_line_notation() {
+        let b = Board::with_queens(
+            (8, 8),
+            vec![
+                (0, 0),
+                (1, 4),
+                (2, 7),
+                (3, 5),
+                (4, 2),
+                (5, 6),
+                (6, 1),
+                (7, 3),
+            ],
+        );
+        let perm = Permutation::try_from(&b).unwrap();
+        assert_eq!(perm.to_string(), "15863724");
+        assert_eq!(perm.to_board(), b);
+    }
+
+    #[test]
+    fn large_permutations_are_space_separated() {
+        let s = "1 3 5 2 10 12 14 4 13 9 6 8 11 7";
+        let perm = s.parse::<Permutation>().unwrap();
+        assert_eq!(perm.len(), 14);
+        assert_eq!(perm.rows()[4], 9);
+        assert_eq!(perm.to_string(), s);
+    }
+
+    #[test]
+    fn permutation_string_round_trip_works() {
+        let perm = "2413".parse::<Permutation>().unwrap();
+        assert_eq!(perm.to_coords(), vec![(0, 1), (1, 3), (2, 0), (3, 2)]);
+        assert_eq!(perm.to_string().parse::<Permutation>(), Ok(perm));
+    }
+
+    #[test]
+    fn invalid_strings_are_rejected() {
+        let invalid_row = |s: &str| Err(PermutationError::InvalidRow(String::from(s)));
+        assert_eq!("".parse::<Permutation>(), invalid_row(""));
+        assert_eq!("1023".parse::<Permutation>(), invalid_row("0"));
+        assert_eq!("12x".parse::<Permutation>(), invalid_row("x"));
+        assert_eq!("1 2 -3".parse::<Permutation>(), invalid_row("-3"));
+        let not_perm = Err(PermutationError::NotPermutation);
+        assert_eq!("1224".parse::<Permutation>(), not_perm);
+        assert_eq!("125".parse::<Permutation>(), not_perm);
+    }
+
+    #[test]
+    fn boards_that_are_not_permutations_are_rejected() {
+        let b = Board::with_queens((4, 3), vec![(0, 0), (1, 2), (3, 1)]);
+        let res = Permutation::try_from(&b);
+        assert_eq!(res, Err(PermutationError::NotSquare((4, 3))));
+        let b = Board::with_queens((3, 3), vec![(0, 0), (1, 2)]);
+        let res = Permutation::try_from(&b);
+        assert_eq!(res, Err(PermutationError::NotPermutation));
+        let b = Board::with_queens((3, 3), vec![(0, 0), (0, 2), (1, 1)]);
+        let res = Permutation::try_from(&b);
+        assert_eq!(res, Err(PermutationError::NotPermutation));
+    }
+}
@@ -0,0 +1,102 @@
+use super::Permutation;
+
+/// The size of the largest permutation that can be ranked. 20! is the
+/// largest factorial that fits in a u64.
+pub const MAX_RANKED_SIZE: usize = 20;
+
+impl Permutation {
+    /// Return the position of this permutation in the lexicographic ordering
+    /// of the permutations of the same size, using its Lehmer code. Returns
+    /// `None` if the permutation is larger than `MAX_RANKED_SIZE`.
+    ///
+    /// Sorting solutions by their queen positions, as `SolutionSet` does,
+    /// also sorts them by rank.
+    pub fn rank(&self) -> Option<u64> {
+        let n = self.len();
+        if n > MAX_RANKED_SIZE {
+            return None;
+        }
+        let rank = self.rows.iter().enumerate().fold(0, |rank, (i, &row)| {
+            let smaller_after = self.rows[i + 1..].iter().filter(|&&r| r < row).count();
+            rank * (n - i) as u64 + smaller_after as u64
+        });
+        Some(rank)
+    }
+
+    /// Return the permutation of the given size with the given rank. Returns
+    /// `None` if the size is larger than `MAX_RANKED_SIZE`, or if the rank is
+    /// not less than the number of permutations of that size.
+    pub fn unrank(rank: u64, n: usize) -> Option<Permutation> {
+        if n > MAX_RANKED_SIZE || rank >= factorial(n) {
+            return None;
+        }
+        let mut remaining = (0..n as u32).collect::<Vec<u32>>();
+        let mut rank = rank;
+        let rows = (0..n)
+            .map(|i| {
+                let place = factorial(n - 1 - i);
+                let index = (rank / place) as usize;
+                rank %= place;
+                remaining.remove(index)
+            })
+            .collect();
+        Some(Permutation { rows })
+    }
+}
+
+fn factorial(n: usize) -> u64 {
+    (1..=n as u64).product()
+}
+
+#[cfg(test)]
+mod permutation_rank_tests {
+    use super::{factorial, MAX_RANKED_SIZE};
+    use permutation::Permutation;
+    use solver::{SearchMode, Solver};
+    use Board;
+
+    #[test]
+    fn permutations_are_ranked_lexicographically() {
+        let perms = ["123", "132", "213", "231", "312", "321"];
+        for (rank, s) in perms.iter().enumerate() {
+            let perm = s.parse::<Permutation>().unwrap();
+            assert_eq!(perm.rank(), Some(rank as u64));
+            assert_eq!(Permutation::unrank(rank as u64, 3), Some(perm));
+        }
+        assert_eq!(Permutation::unrank(6, 3), None);
+    }
+
+    #[test]
+    fn rank_round_trip_works() {
+        let perm = "15863724".parse::<Permutation>().unwrap();
+        let rank = perm.rank().unwrap();
+        assert_eq!(Permutation::unrank(rank, 8), Some(perm));
+    }
+
+    #[test]
+    fn largest_permutations_can_be_ranked() {
+        let rows = (0..MAX_RANKED_SIZE as u32).rev().collect::<Vec<u32>>();
+        let perm = Permutation::from_rows(rows).unwrap();
+        let rank = factorial(MAX_RANKED_SIZE) - 1;
+        assert_eq!(perm.rank(), Some(rank));
+        assert_eq!(Permutation::unrank(rank, MAX_RANKED_SIZE), Some(perm));
+        let rows = (0..MAX_RANKED_SIZE as u32 + 1).collect::<Vec<u32>>();
+        assert_eq!(Permutation::from_rows(rows).unwrap().rank(), None);
+        assert_eq!(Permutation::unrank(0, MAX_RANKED_SIZE + 1), None);
+    }
+
+    #[test]
+    fn sorted_solutions_are_sorted_by_rank() {
+        let mut solver = Solver::from(Board::from((8, 8)));
+        solver.set_search_mode(SearchMode::Bitboard);
+        let ranks = solver
+            .solve_sorted()
+            .solutions()
+            .iter()
+            .map(|coords| Permutation::from_coords(coords, 8).unwrap())
+            .map(|perm| perm.rank().unwrap())
+            .collect::<Vec<u64>>();
+        assert_eq!(ranks.len(), 92);
+        assert!(ranks.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,792 @@
+use position::CoordIter;
+use position_types::{CoordList, PosCoords};
+use topology::{gcd, Topology};
+
+/// The kinds of chess piece that can be placed on a board. Every piece on a
+/// board is of the same kind, and a solution places the largest number of
+/// pieces that can be placed without any two attacking each other.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Piece {
+    #[default]
+    Queen,
+    Rook,
+    Bishop,
+    Knight,
+    King,
+    /// A piece that moves as either a queen or a knight.
+    Amazon,
+}
+
+/// A family of parallel lines that a piece can slide along.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Line {
+    Column,
+    Row,
+    /// Diagonals running from the bottom-left corner towards the top-right
+    /// corner, where `x - y` is constant.
+    Diagonal,
+    /// Diagonals running from the top-left corner towards the bottom-right
+    /// corner, where `x + y` is constant.
+    AntiDiagonal,
+}
+
+static PIECES: &[Piece] = &[
+    Piece::Queen,
+    Piece::Rook,
+    Piece::Bishop,
+    Piece::Knight,
+    Piece::King,
+    Piece::Amazon,
+];
+
+static ALL_LINES: &[Line] = &[Line::Column, Line::Row, Line::Diagonal, Line::AntiDiagonal];
+static ORTHOGONAL_LINES: &[Line] = &[Line::Column, Line::Row];
+static DIAGONAL_LINES: &[Line] = &[Line::Diagonal, Line::AntiDiagonal];
+
+static KNIGHT_LEAPS: &[(i64, i64)] = &[
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+static KING_LEAPS: &[(i64, i64)] = &[
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+impl Piece {
+    /// Return a slice containing every kind of piece.
+    pub fn all() -> &'static [Piece] {
+        PIECES
+    }
+
+    /// Return the character used to draw the piece, using the letters of
+    /// algebraic chess notation, and 'A' for an amazon.
+    pub fn symbol(self) -> char {
+        match self {
+            Piece::Queen => 'Q',
+            Piece::Rook => 'R',
+            Piece::Bishop => 'B',
+            Piece::Knight => 'N',
+            Piece::King => 'K',
+            Piece::Amazon => 'A',
+        }
+    }
+
+    /// Return the Unicode chess glyph used to draw the piece. There is no
+    /// glyph for an amazon, so it is drawn using its symbol.
+    pub fn glyph(self) -> char {
+        match self {
+            Piece::Queen => '♛',
+            Piece::Rook => '♜',
+            Piece::Bishop => '♝',
+            Piece::Knight => '♞',
+            Piece::King => '♚',
+            Piece::Amazon => 'A',
+        }
+    }
+
+    /// Return the outlined Unicode chess glyph used to draw the piece when it
+    /// is in conflict with another piece. There is no such glyph for an
+    /// amazon.
+    pub fn outline_glyph(self) -> Option<char> {
+        match self {
+            Piece::Queen => Some('♕'),
+            Piece::Rook => Some('♖'),
+            Piece::Bishop => Some('♗'),
+            Piece::Knight => Some('♘'),
+            Piece::King => Some('♔'),
+            Piece::Amazon => None,
+        }
+    }
+
+    /// Return the piece drawn using the given character, if there is one.
+    pub fn from_symbol(c: char) -> Option<Piece> {
+        PIECES.iter().cloned().find(|piece| piece.symbol() == c)
+    }
+
+    /// Returns true if the piece is a queen. Boards and solution sets holding
+    /// queens are serialized without their piece, as it is the default.
+    pub fn is_queen(&self) -> bool {
+        *self == Piece::Queen
+    }
+
+    /// Return the lines that the piece can slide along. A sliding piece
+    /// cannot pass through a blocked square.
+    pub fn lines(self) -> &'static [Line] {
+        match self {
+            Piece::Queen | Piece::Amazon => ALL_LINES,
+            Piece::Rook => ORTHOGONAL_LINES,
+            Piece::Bishop => DIAGONAL_LINES,
+            Piece::Knight | Piece::King => &[],
+        }
+    }
+
+    /// Return the offsets of the squares that the piece can leap to. Leaps
+    /// are not affected by blocked squares.
+    pub fn leaps(self) -> &'static [(i64, i64)] {
+        match self {
+            Piece::Knight | Piece::Amazon => KNIGHT_LEAPS,
+            Piece::King => KING_LEAPS,
+            Piece::Queen | Piece::Rook | Piece::Bishop => &[],
+        }
+    }
+
+    /// Returns true if the piece attacks along every row and column, so that
+    /// a board holds at most one piece in each line, unless it has blocked
+    /// squares.
+    pub fn holds_one_per_line(self) -> bool {
+        self.lines().contains(&Line::Row) && self.lines().contains(&Line::Column)
+    }
+
+    /// Return the number of pieces that a solution holds on an empty board
+    /// with the given dimensions and topology. For rooks, bishops and kings,
+    /// and for knights on a flat board or a toroidal board with even
+    /// dimensions, this is the largest number of pieces that can be placed
+    /// such that no two attack each other. For bishops, this is found by
+    /// matching the diagonals to the anti-diagonals.
+    ///
+    /// Otherwise, this is an upper bound that is only reached on some boards,
+    /// so a board may have no solutions. Queens and amazons are bounded by
+    /// the rows and columns, and no 9x9 board holds nine amazons. Knights on
+    /// a toroidal board with an odd dimension each attack the same number of
+    /// squares, so at most half of the squares hold a knight. Use
+    /// `search_max_independent` to find the largest number exactly.
+    pub fn max_independent(self, dims: PosCoords, topology: Topology) -> u32 {
+        let (short, long) = (dims.0.min(dims.1), dims.0.max(dims.1));
+        match self {
+            _ if short == 0 => 0,
+            Piece::Queen | Piece::Rook | Piece::Amazon => short,
+            Piece::Bishop => max_bishops(dims, topology),
+            Piece::King | Piece::Knight if !topology.is_flat() => {
+                self.max_independent_on_torus(dims)
+            }
+            Piece::King => short.div_ceil(2) * long.div_ceil(2),
+            Piece::Knight => match short {
+                1 => long,
+                // Knights on a board with two rows are placed in 2x2 blocks,
+                // separated by two empty columns.
+                2 => 4 * (long / 4) + 2 * (long % 4).min(2),
+                _ => (short * long).div_ceil(2),
+            },
+        }
+    }
+
+    /// Return the number of kings or knights that a solution holds on an
+    /// empty toroidal board. For kings, a line with fewer than four squares
+    /// wraps around next to itself, and so holds no more kings than a line of
+    /// two squares. Knights can fill every other square of a board with even
+    /// dimensions, which bounds the count on other boards, except that the
+    /// single square of a 1x1 board holds a knight.
+    fn max_independent_on_torus(self, (width, height): PosCoords) -> u32 {
+        match self {
+            Piece::King => {
+                let wrap = |len: u32| if len < 4 { 2 } else { len };
+                let (width, height) = (wrap(width), wrap(height));
+                (width * (height / 2) / 2).min(height * (width / 2) / 2)
+            }
+            _ => (width * height / 2).max(1),
+        }
+    }
+
+    /// Search for the largest number of pieces that can be placed on an empty
+    /// board with the given dimensions and topology, such that no two attack
+    /// each other. Unlike `max_independent`, this is exact for every piece,
+    /// but the search takes exponential time, and can take seconds on boards
+    /// larger than 10x10. Every square of a toroidal board looks the same, so
+    /// the search places a piece on the first square of a toroidal board.
+    pub fn search_max_independent(self, dims: PosCoords, topology: Topology) -> u32 {
+        let squares = CoordIter::from(dims).collect::<CoordList>();
+        if squares.is_empty() {
+            return 0;
+        }
+        let num_words = squares.len().div_ceil(64);
+        let attacks = squares
+            .iter()
+            .map(|&a| {
+                let mut words = vec![0u64; num_words];
+                for (i, &b) in squares.iter().enumerate() {
+                    if self.attacks(a, b, dims, &[], topology) {
+                        words[i / 64] |= 1 << (i % 64);
+                    }
+                }
+                words
+            })
+            .collect::<Vec<Vec<u64>>>();
+        let mut candidates = vec![!0u64; num_words];
+        if squares.len() % 64 != 0 {
+            candidates[num_words - 1] = (1 << (squares.len() % 64)) - 1;
+        }
+        let mut best = 0;
+        if topology.is_flat() {
+            grow_independent(&attacks, candidates, 0, &mut best);
+        } else {
+            remove_attacked(&mut candidates, &attacks[0]);
+            grow_independent(&attacks, candidates, 1, &mut best);
+        }
+        best
+    }
+
+    /// Returns true if pieces at positions `a` and `b` would attack each
+    /// other, meaning that one can slide to the other along one of the
+    /// piece's lines without passing a blocked square, or leap to it. On a
+    /// toroidal board, slides and leaps wrap around the edges of the board.
+    /// `blocked` must be sorted.
+    pub fn attacks(
+        self,
+        a: PosCoords,
+        b: PosCoords,
+        dims: PosCoords,
+        blocked: &[PosCoords],
+        topology: Topology,
+    ) -> bool {
+        let leaps_to_b = |&leap| topology.step(a, dims, leap) == Some(b);
+        if a == b || self.leaps().iter().any(leaps_to_b) {
+            return true;
+        }
+        let (dx, dy) = topology.offset(a, b, dims);
+        match topology {
+            Topology::Flat => self.slides_to(a, (dx, dy), blocked),
+            Topology::Toroidal if blocked.is_empty() => {
+                let period = gcd(dims.0, dims.1) as i64;
+                self.lines()
+                    .iter()
+                    .any(|&line| line.wraps_between((dx, dy), period))
+            }
+            Topology::Toroidal => {
+                let moves = self.get_moves(a, dims, blocked, topology);
+                moves.binary_search(&b).is_ok()
+            }
+        }
+    }
+
+    /// Returns true if a piece at position `a` on a flat board can slide the
+    /// given offset along one of its lines, without passing a blocked square.
+    fn slides_to(self, a: PosCoords, (dx, dy): (i64, i64), blocked: &[PosCoords]) -> bool {
+        let slides = Line::between((dx, dy)).is_some_and(|line| self.lines().contains(&line));
+        if !slides {
+            return false;
+        }
+        let (step_x, step_y) = (dx.signum(), dy.signum());
+        let dist = dx.abs().max(dy.abs());
+        !(1..dist).any(|k| {
+            let square = (
+                (a.0 as i64 + k * step_x) as u32,
+                (a.1 as i64 + k * step_y) as u32,
+            );
+            blocked.binary_search(&square).is_ok()
+        })
+    }
+
+    /// Get the coordinates of the squares that a piece at the given position
+    /// attacks, including its own position. Slides stop at the edge of a flat
+    /// board, or before the first blocked square. On a toroidal board, slides
+    /// wrap around until they return to the piece. `blocked` must be sorted.
+    pub fn get_moves(
+        self,
+        pos: PosCoords,
+        dims: PosCoords,
+        blocked: &[PosCoords],
+        topology: Topology,
+    ) -> CoordList {
+        let mut moves = self
+            .lines()
+            .iter()
+            .flat_map(|line| line.directions().iter())
+            .flat_map(|&step| get_slide(pos, dims, step, blocked, topology))
+            .chain(
+                self.leaps()
+                    .iter()
+                    .filter_map(|&offset| topology.step(pos, dims, offset)),
+            )
+            .collect::<CoordList>();
+        moves.push(pos);
+        moves.sort();
+        moves.dedup();
+        moves
+    }
+
+    /// Return the squares on a board that are attacked by the given pieces.
+    pub fn get_contested_spaces(
+        self,
+        pieces: &[PosCoords],
+        dims: PosCoords,
+        blocked: &[PosCoords],
+        topology: Topology,
+    ) -> CoordList {
+        let mut coords = pieces
+            .iter()
+            .flat_map(|&pos| self.get_moves(pos, dims, blocked, topology))
+            .collect::<CoordList>();
+        coords.sort();
+        coords.dedup();
+        coords
+    }
+}
+
+impl Line {
+    /// Return a slice containing every family of lines.
+    pub fn all() -> &'static [Line] {
+        ALL_LINES
+    }
+
+    /// Return the family of lines connecting two squares, given the offset
+    /// from one to the other, if they share a line.
+    pub fn between((dx, dy): (i64, i64)) -> Option<Line> {
+        match (dx, dy) {
+            (0, 0) => None,
+            (0, _) => Some(Line::Column),
+            (_, 0) => Some(Line::Row),
+            _ if dx == dy => Some(Line::Diagonal),
+            _ if dx == -dy => Some(Line::AntiDiagonal),
+            _ => None,
+        }
+    }
+
+    /// Return the number of lines in this family on a board with the given
+    /// dimensions.
+    pub fn num_lines(self, dims: PosCoords) -> usize {
+        match self {
+            Line::Column => dims.0 as usize,
+            Line::Row => dims.1 as usize,
+            Line::Diagonal | Line::AntiDiagonal => (dims.0 + dims.1) as usize,
+        }
+    }
+
+    /// Return the index of the line in this family that passes through a
+    /// position, paired with the offset of the position along that line.
+    pub fn locate(self, (x, y): PosCoords, dims: PosCoords) -> (usize, u32) {
+        match self {
+            Line::Column => (x as usize, y),
+            Line::Row => (y as usize, x),
+            Line::Diagonal => ((x + dims.1 - 1 - y) as usize, x),
+            Line::AntiDiagonal => ((x + y) as usize, x),
+        }
+    }
+
+    /// Returns true if two squares separated by the given offset share a line
+    /// in this family on a toroidal board without blocked squares. The offset
+    /// must be reduced modulo the dimensions, and `period` is the greatest
+    /// common divisor of the dimensions. A diagonal wraps around through
+    /// every square whose offset along each axis is equal modulo `period`.
+    pub fn wraps_between(self, (dx, dy): (i64, i64), period: i64) -> bool {
+        match self {
+            Line::Column => dx == 0,
+            Line::Row => dy == 0,
+            Line::Diagonal => (dx - dy) % period == 0,
+            Line::AntiDiagonal => (dx + dy) % period == 0,
+        }
+    }
+
+    /// Return the two directions along the lines in this family.
+    fn directions(self) -> &'static [(i64, i64)] {
+        match self {
+            Line::Column => &[(0, 1), (0, -1)],
+            Line::Row => &[(1, 0), (-1, 0)],
+            Line::Diagonal => &[(1, 1), (-1, -1)],
+            Line::AntiDiagonal => &[(1, -1), (-1, 1)],
+        }
+    }
+}
+
+/// Return the largest number of bishops that can be placed on an empty board
+/// without attacking each other. Two bishops attack each other if they share
+/// a diagonal or an anti-diagonal, so each square joins the two lines passing
+/// through it, and the bishops form a maximum matching between the diagonals
+/// and the anti-diagonals. On a toroidal board, the diagonals wrap around
+/// through every square whose coordinates have the same difference, or sum,
+/// modulo the greatest common divisor of the dimensions.
+fn max_bishops(dims: PosCoords, topology: Topology) -> u32 {
+    let period = gcd(dims.0, dims.1);
+    let num_lines = match topology {
+        Topology::Flat => Line::Diagonal.num_lines(dims),
+        Topology::Toroidal => period as usize,
+    };
+    let mut joins = vec![Vec::new(); num_lines];
+    for (x, y) in CoordIter::from(dims) {
+        let (diagonal, anti_diagonal) = match topology {
+            Topology::Flat => (
+                Line::Diagonal.locate((x, y), dims).0,
+                Line::AntiDiagonal.locate((x, y), dims).0,
+            ),
+            Topology::Toroidal => (
+                ((x + period - y % period) % period) as usize,
+                ((x + y) % period) as usize,
+            ),
+        };
+        joins[diagonal].push(anti_diagonal);
+    }
+    let mut matches = vec![None; num_lines];
+    (0..joins.len())
+        .filter(|&diagonal| {
+            let mut visited = vec![false; matches.len()];
+            find_augmenting_path(diagonal, &joins, &mut matches, &mut visited)
+        })
+        .count() as u32
+}
+
+/// Try to match a diagonal to an anti-diagonal, moving diagonals that were
+/// already matched onto other anti-diagonals if needed. Returns true if the
+/// matching grew.
+fn find_augmenting_path(
+    diagonal: usize,
+    joins: &[Vec<usize>],
+    matches: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for &anti_diagonal in joins[diagonal].iter() {
+        if visited[anti_diagonal] {
+            continue;
+        }
+        visited[anti_diagonal] = true;
+        let is_free = match matches[anti_diagonal] {
+            Some(other) => find_augmenting_path(other, joins, matches, visited),
+            None => true,
+        };
+        if is_free {
+            matches[anti_diagonal] = Some(diagonal);
+            return true;
+        }
+    }
+    false
+}
+
+/// Add squares to an independent set of pieces, one candidate square at a
+/// time, and record the size of the largest set found in `best`. Each
+/// candidate is either taken, removing the squares it attacks, or skipped.
+/// Branches that cannot beat `best` are abandoned, as only one square of each
+/// pair of candidates attacking each other can be taken.
+fn grow_independent(attacks: &[Vec<u64>], candidates: Vec<u64>, size: u32, best: &mut u32) {
+    if size + count_unpaired(attacks, &candidates) <= *best {
+        return;
+    }
+    let i = match first_square(&candidates) {
+        Some(i) => i,
+        None => {
+            *best = size;
+            return;
+        }
+    };
+    let mut taken = candidates.clone();
+    remove_attacked(&mut taken, &attacks[i]);
+    grow_independent(attacks, taken, size + 1, best);
+    let mut skipped = candidates;
+    skipped[i / 64] &= !(1 << (i % 64));
+    grow_independent(attacks, skipped, size, best);
+}
+
+/// Pair up candidate squares that attack each other, and return the number
+/// of pairs plus the number of squares left unpaired. This bounds the number
+/// of pieces that can be placed on the candidates.
+fn count_unpaired(attacks: &[Vec<u64>], candidates: &[u64]) -> u32 {
+    let mut left = candidates.to_vec();
+    let mut count = 0;
+    while let Some(i) = first_square(&left) {
+        left[i / 64] &= !(1 << (i % 64));
+        let partners = left
+            .iter()
+            .zip(attacks[i].iter())
+            .map(|(&word, &attacked)| word & attacked)
+            .collect::<Vec<u64>>();
+        if let Some(j) = first_square(&partners) {
+            left[j / 64] &= !(1 << (j % 64));
+        }
+        count += 1;
+    }
+    count
+}
+
+/// Return the index of the first square in a set of squares, if any.
+fn first_square(squares: &[u64]) -> Option<usize> {
+    squares
+        .iter()
+        .position(|&word| word != 0)
+        .map(|word| word * 64 + squares[word].trailing_zeros() as usize)
+}
+
+/// Remove the squares attacked by a piece from a set of candidate squares.
+fn remove_attacked(candidates: &mut [u64], attacks: &[u64]) {
+    for (word, attacked) in candidates.iter_mut().zip(attacks.iter()) {
+        *word &= !attacked;
+    }
+}
+
+/// Return the squares a piece can reach by sliding in a single direction,
+/// stopping at the edge of a flat board, before the first blocked square, or
+/// once the slide wraps back around to the piece.
+fn get_slide(
+    pos: PosCoords,
+    dims: PosCoords,
+    step: (i64, i64),
+    blocked: &[PosCoords],
+    topology: Topology,
+) -> CoordList {
+    let mut slide = CoordList::new();
+    let mut next = topology.step(pos, dims, step);
+    while let Some(square) = next {
+        if square == pos || blocked.binary_search(&square).is_ok() {
+            break;
+        }
+        slide.push(square);
+        next = topology.step(square, dims, step);
+    }
+    slide
+}
+
+#[cfg(test)]
+mod piece_tests {
+    use super::{Line, Piece};
+    use checker::check_board;
+    use position::CoordIter;
+    use queen::get_queen_moves;
+    use topology::Topology::{Flat, Toroidal};
+    use {Board, CoordList};
+
+    #[test]
+    fn symbols_round_trip() {
+        for &piece in Piece::all() {
+            assert_eq!(Piece::from_symbol(piece.symbol()), Some(piece));
+        }
+        assert_eq!(Piece::from_symbol('x'), None);
+    }
+
+    #[test]
+    fn queen_moves_match_get_queen_moves() {
+        for &pos in [(0, 0), (2, 2), (7, 7), (3, 6)].iter() {
+            let expected = get_queen_moves(pos, &Board::new());
+            assert_eq!(Piece::Queen.get_moves(pos, (8, 8), &[], Flat), expected);
+        }
+    }
+
+    ///   01234
+    ///   -----
+    /// 4| x x |
+    /// 3|x   x|
+    /// 2|  N  |
+    /// 1|x   x|
+    /// 0| x x |
+    ///   -----
+    #[test]
+    fn knight_moves_are_leaps() {
+        let expected: CoordList = vec![
+            (0, 1),
+            (0, 3),
+            (1, 0),
+            (1, 4),
+            (2, 2),
+            (3, 0),
+            (3, 4),
+            (4, 1),
+            (4, 3),
+        ];
+        let blocked = vec![(1, 2), (2, 1), (2, 3), (3, 2)];
+        assert_eq!(
+            Piece::Knight.get_moves((2, 2), (5, 5), &blocked, Flat),
+            expected
+        );
+        assert_eq!(
+            Piece::Knight.get_moves((0, 0), (5, 5), &[], Flat),
+            vec![(0, 0), (1, 2), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn piece_moves_are_combined_from_lines_and_leaps() {
+        let dims = (8, 8);
+        let count = |piece: Piece, pos| piece.get_moves(pos, dims, &[], Flat).len() - 1;
+        assert_eq!(count(Piece::Rook, (3, 3)), 14);
+        assert_eq!(count(Piece::Bishop, (3, 3)), 13);
+        assert_eq!(count(Piece::King, (3, 3)), 8);
+        assert_eq!(count(Piece::King, (0, 0)), 3);
+        assert_eq!(count(Piece::Knight, (3, 3)), 8);
+        assert_eq!(count(Piece::Amazon, (3, 3)), 27 + 8);
+        assert_eq!(count(Piece::Queen, (3, 3)), 27);
+    }
+
+    #[test]
+    fn attacks_match_moves() {
+        let blocked = [(2, 2), (4, 1)];
+        for &(dims, topology) in [((6, 5), Flat), ((6, 5), Toroidal), ((6, 4), Toroidal)].iter() {
+            for blocked in [&blocked[..], &[]].iter() {
+                for &piece in Piece::all() {
+                    let a = (3, 2);
+                    let moves = piece.get_moves(a, dims, blocked, topology);
+                    for b in CoordIter::from(dims).filter(|b| !blocked.contains(b)) {
+                        let expected = moves.contains(&b);
+                        let actual = piece.attacks(a, b, dims, blocked, topology);
+                        assert_eq!(actual, expected, "{:?} {:?} {:?}", piece, topology, b);
+                    }
+                }
+            }
+        }
+    }
+
+    ///   01234
+    ///   -----
+    /// 4|xxxxQ|
+    /// 3|x  xx|
+    /// 2| xx x|
+    /// 1| xx x|
+    /// 0|x  xx|
+    ///   -----
+    #[test]
+    fn toroidal_moves_wrap_around() {
+        let moves = Piece::Queen.get_moves((4, 4), (5, 5), &[], Toroidal);
+        assert_eq!(moves.len() - 1, 16);
+        assert!(moves.contains(&(0, 0)));
+        assert!(moves.contains(&(1, 2)));
+        assert!(!moves.contains(&(1, 3)));
+        let moves = Piece::Knight.get_moves((0, 0), (5, 5), &[], Toroidal);
+        assert_eq!(moves.len(), 9);
+    }
+
+    /// The number of pieces in a solution on an 8x8 board. For queens and
+    /// amazons this is the bound given by the rows and columns, which eight
+    /// queens reach but eight amazons do not.
+    #[test]
+    fn max_independent_counts_are_known_for_8x8() {
+        let dims = (8, 8);
+        assert_eq!(Piece::Queen.max_independent(dims, Flat), 8);
+        assert_eq!(Piece::Rook.max_independent(dims, Flat), 8);
+        assert_eq!(Piece::Bishop.max_independent(dims, Flat), 14);
+        assert_eq!(Piece::Knight.max_independent(dims, Flat), 32);
+        assert_eq!(Piece::King.max_independent(dims, Flat), 16);
+        assert_eq!(Piece::Amazon.max_independent(dims, Flat), 8);
+        assert_eq!(Piece::Amazon.search_max_independent(dims, Flat), 6);
+    }
+
+    /// At most eight amazons fit on a 9x9 board, so the bound of nine cannot
+    /// be reached and the board has no solutions.
+    #[test]
+    fn amazons_fall_short_of_the_bound_on_9x9() {
+        assert_eq!(Piece::Amazon.max_independent((9, 9), Flat), 9);
+        assert_eq!(Piece::Amazon.search_max_independent((9, 9), Flat), 8);
+    }
+
+    /// Placements reaching the maximal counts on an 8x8 board: knights on
+    /// every dark square, bishops along the bottom row and the middle of the
+    /// top row, and kings on every square with even coordinates.
+    #[test]
+    fn max_independent_counts_are_reached_on_8x8() {
+        let placements: [(Piece, CoordList); 3] = [
+            (
+                Piece::Knight,
+                CoordIter::from((8, 8))
+                    .filter(|&(x, y)| (x + y) % 2 == 0)
+                    .collect(),
+            ),
+            (
+                Piece::Bishop,
+                (0..8)
+                    .map(|x| (x, 0))
+                    .chain((1..7).map(|x| (x, 7)))
+                    .collect(),
+            ),
+            (
+                Piece::King,
+                CoordIter::from((8, 8))
+                    .filter(|&(x, y)| x % 2 == 0 && y % 2 == 0)
+                    .collect(),
+            ),
+        ];
+        for &(piece, ref queens) in placements.iter() {
+            let mut b = Board::with_queens((8, 8), queens.clone());
+            b.set_piece(piece);
+            assert!(check_board(&b).is_solved, "{:?}", piece);
+        }
+    }
+
+    #[test]
+    fn max_independent_counts_handle_narrow_boards() {
+        assert_eq!(Piece::Knight.max_independent((2, 2), Flat), 4);
+        assert_eq!(Piece::Knight.max_independent((2, 3), Flat), 4);
+        assert_eq!(Piece::Knight.max_independent((6, 2), Flat), 8);
+        assert_eq!(Piece::Knight.max_independent((1, 5), Flat), 5);
+        assert_eq!(Piece::Knight.max_independent((3, 3), Flat), 5);
+        assert_eq!(Piece::Bishop.max_independent((1, 5), Flat), 5);
+        assert_eq!(Piece::Bishop.max_independent((3, 5), Flat), 7);
+        assert_eq!(Piece::Bishop.max_independent((2, 3), Flat), 4);
+        assert_eq!(Piece::Bishop.max_independent((2, 5), Flat), 6);
+        assert_eq!(Piece::Bishop.max_independent((3, 4), Flat), 6);
+        assert_eq!(Piece::Bishop.max_independent((6, 3), Flat), 8);
+        assert_eq!(Piece::Bishop.max_independent((4, 5), Flat), 8);
+        assert_eq!(Piece::Bishop.max_independent((2, 10), Flat), 10);
+        assert_eq!(Piece::King.max_independent((3, 5), Flat), 6);
+        assert_eq!(Piece::King.max_independent((0, 5), Flat), 0);
+    }
+
+    /// Seven bishops fit on a 3x5 board, with three along each short edge and
+    /// one in the middle of the board.
+    #[test]
+    fn max_independent_bishops_are_reached_on_3x5() {
+        let queens = vec![(0, 0), (1, 0), (2, 0), (1, 2), (0, 4), (1, 4), (2, 4)];
+        let mut b = Board::from((3, 5));
+        b.set_piece(Piece::Bishop);
+        for pos in queens {
+            b.try_add_queen(pos).unwrap();
+        }
+        assert!(check_board(&b).is_solved);
+    }
+
+    /// The number of pieces in a solution on toroidal boards, where each piece
+    /// attacks more squares than on a flat board.
+    #[test]
+    fn max_independent_counts_handle_toroidal_boards() {
+        assert_eq!(Piece::Rook.max_independent((5, 3), Toroidal), 3);
+        assert_eq!(Piece::King.max_independent((3, 3), Toroidal), 1);
+        assert_eq!(Piece::King.max_independent((5, 5), Toroidal), 5);
+        assert_eq!(Piece::King.max_independent((4, 7), Toroidal), 6);
+        assert_eq!(Piece::King.max_independent((1, 6), Toroidal), 3);
+        assert_eq!(Piece::Knight.max_independent((4, 4), Toroidal), 8);
+        assert_eq!(Piece::Knight.max_independent((5, 5), Toroidal), 12);
+        assert_eq!(Piece::Knight.max_independent((2, 7), Toroidal), 7);
+        assert_eq!(Piece::Knight.max_independent((1, 1), Toroidal), 1);
+        assert_eq!(Piece::Bishop.max_independent((5, 5), Toroidal), 5);
+        assert_eq!(Piece::Bishop.max_independent((4, 6), Toroidal), 2);
+    }
+
+    /// Knights on toroidal boards with an odd dimension fall short of the
+    /// bound given by `max_independent`.
+    #[test]
+    fn toroidal_knights_are_counted_by_searching() {
+        assert_eq!(Piece::Knight.search_max_independent((5, 5), Toroidal), 5);
+        assert_eq!(Piece::Knight.search_max_independent((2, 7), Toroidal), 5);
+        assert_eq!(Piece::Knight.search_max_independent((7, 7), Toroidal), 14);
+        assert_eq!(Piece::Knight.search_max_independent((4, 4), Toroidal), 8);
+        assert_eq!(Piece::Knight.search_max_independent((0, 4), Toroidal), 0);
+    }
+
+    #[test]
+    fn searched_counts_match_max_independent_on_flat_boards() {
+        for &piece in [Piece::Rook, Piece::Bishop, Piece::Knight, Piece::King].iter() {
+            for &dims in [(1, 5), (2, 6), (3, 5), (5, 5), (6, 4)].iter() {
+                let expected = piece.max_independent(dims, Flat);
+                assert_eq!(piece.search_max_independent(dims, Flat), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn lines_locate_positions() {
+        let dims = (5, 3);
+        assert_eq!(Line::Column.locate((4, 2), dims), (4, 2));
+        assert_eq!(Line::Row.locate((4, 2), dims), (2, 4));
+        assert_eq!(Line::Diagonal.locate((0, 2), dims), (0, 0));
+        assert_eq!(Line::Diagonal.locate((4, 0), dims), (6, 4));
+        assert_eq!(Line::AntiDiagonal.locate((4, 2), dims), (6, 4));
+        assert_eq!(Line::between((2, -2)), Some(Line::AntiDiagonal));
+        assert_eq!(Line::between((1, 2)), None);
+    }
+}
@@ -1,4 +1,6 @@
 use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
 
 mod coord_iter;
 
@@ -20,9 +22,60 @@ pub enum PosError {
     OutOfBounds,
 }
 
+/// Board errors. Returned if a queen cannot be added to a board, extending
+/// `PosError` with the position that caused the error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardError {
+    /// The position is not within the bounds of the board.
+    OutOfBounds(PosCoords),
+    /// The square at the position already holds a queen.
+    DuplicateSquare(PosCoords),
+    /// The board already holds its target number of queens, so adding
+    /// another queen at the position would always cause a conflict.
+    ExceedsCapacity(PosCoords),
+    /// The square at the position is blocked.
+    BlockedSquare(PosCoords),
+}
+
+impl BoardError {
+    /// Return the position that caused the error.
+    pub fn pos(&self) -> PosCoords {
+        match *self {
+            BoardError::OutOfBounds(pos)
+            | BoardError::DuplicateSquare(pos)
+            | BoardError::ExceedsCapacity(pos)
+            | BoardError::BlockedSquare(pos) => pos,
+        }
+    }
+}
+
+impl From<(PosError, PosCoords)> for BoardError {
+    fn from(err: (PosError, PosCoords)) -> BoardError {
+        match err {
+            (PosError::OutOfBounds, pos) => BoardError::OutOfBounds(pos),
+        }
+    }
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BoardError::OutOfBounds(pos) => write!(f, "position {:?} is out of bounds", pos),
+            BoardError::DuplicateSquare(pos) => write!(f, "position {:?} already holds a queen", pos),
+            BoardError::ExceedsCapacity(pos) => {
+                write!(f, "cannot add queen at {:?}, the board is full", pos)
+            }
+            BoardError::BlockedSquare(pos) => write!(f, "position {:?} is blocked", pos),
+        }
+    }
+}
+
+impl Error for BoardError {}
+
 /// Module used to import the different position types, and the error enum
 /// that can be returned in the event of an invalid coordinate pair.
 pub mod position_types {
+    pub use super::BoardError;
     pub use super::CoordList;
     pub use super::PosCoords;
     pub use super::PosError;
@@ -1,10 +1,14 @@
+use piece::Piece;
 use position_types::{CoordList, PosCoords};
 use std::cmp::min;
+use topology::{gcd, Topology};
+use Board;
 
 /// Return a set of all of the contested spaces on the board, given the
-/// positions of each queen, and the dimensions of the board.
-pub fn get_contested_spaces(queens: Vec<PosCoords>, dims: PosCoords) -> CoordList {
-    let mut coords = get_queen_move_sets(queens, dims)
+/// positions of each queen. Each queen's rays stop before the first blocked
+/// square on the board, and wrap around the edges of a toroidal board.
+pub fn get_contested_spaces(queens: Vec<PosCoords>, board: &Board) -> CoordList {
+    let mut coords = get_queen_move_sets(queens, board)
         .into_iter()
         .flatten()
         .collect::<CoordList>();
@@ -14,23 +18,31 @@ pub fn get_contested_spaces(queens: Vec<PosCoords>, dims: PosCoords) -> CoordLis
 }
 
 /// Returns a vector of coordinate sets representing each queen's possible moves.
-pub fn get_queen_move_sets(queens: Vec<PosCoords>, dims: PosCoords) -> Vec<CoordList> {
+pub fn get_queen_move_sets(queens: Vec<PosCoords>, board: &Board) -> Vec<CoordList> {
     queens
         .into_iter()
-        .map(|pos| get_queen_moves(pos, dims))
+        .map(|pos| get_queen_moves(pos, board))
         .collect()
 }
 
 /// Get the coordinates of the possible moves that a queen can
-/// potential make. This identifies the squares a queen is contesting.
-pub fn get_queen_moves(pos: PosCoords, dims: PosCoords) -> CoordList {
+/// potential make. This identifies the squares a queen is contesting,
+/// including its own position. Each ray stops before the first blocked
+/// square on the board, and on a toroidal board, the diagonals wrap around
+/// the edges of the board.
+pub fn get_queen_moves(pos: PosCoords, board: &Board) -> CoordList {
+    let (dims, topology) = (board.dims(), board.topology());
+    let blocked = board.get_blocked_positions();
+    if !blocked.is_empty() {
+        return Piece::Queen.get_moves(pos, dims, &blocked, topology);
+    }
     let mut moves = [
         get_vert_moves(pos, dims),
         get_horiz_moves(pos, dims),
-        get_nw_moves(pos, dims),
-        get_ne_moves(pos, dims),
-        get_sw_moves(pos, dims),
-        get_se_moves(pos, dims),
+        get_nw_moves(pos, dims, topology),
+        get_ne_moves(pos, dims, topology),
+        get_sw_moves(pos, dims, topology),
+        get_se_moves(pos, dims, topology),
     ].into_iter()
         .flatten()
         .cloned()
@@ -40,6 +52,14 @@ pub fn get_queen_moves(pos: PosCoords, dims: PosCoords) -> CoordList {
     moves
 }
 
+/// Returns true if queens placed at positions `a` and `b` on the board would
+/// contest each other, meaning they share a row, a column, or a diagonal,
+/// and there is no blocked square between them.
+pub fn queens_conflict(a: PosCoords, b: PosCoords, board: &Board) -> bool {
+    let blocked = board.get_blocked_positions();
+    Piece::Queen.attacks(a, b, board.dims(), &blocked, board.topology())
+}
+
 /// This function will return a vector of the vertical moves a queen at
 /// a given position `pos` can make.
 fn get_vert_moves(pos: PosCoords, dims: PosCoords) -> Vec<PosCoords> {
@@ -54,7 +74,10 @@ fn get_horiz_moves(pos: PosCoords, dims: PosCoords) -> Vec<PosCoords> {
 
 /// This function will return a set of the possible diagonal moves
 /// going up and to the left.
-fn get_nw_moves(pos: PosCoords, dims: PosCoords) -> Vec<PosCoords> {
+fn get_nw_moves(pos: PosCoords, dims: PosCoords, topology: Topology) -> Vec<PosCoords> {
+    if topology == Topology::Toroidal {
+        return get_wrapped_moves(pos, dims, (-1, 1));
+    }
     let dis_to_edge = min(pos.0 + 1, dims.1 - pos.1);
     (0..dis_to_edge)
         .map(|delta| (pos.0 - delta, pos.1 + delta))
@@ -63,7 +86,10 @@ fn get_nw_moves(pos: PosCoords, dims: PosCoords) -> Vec<PosCoords> {
 
 /// This function will return a set of the possible diagonal moves
 /// going up and to the right.
-fn get_ne_moves(pos: PosCoords, dims: PosCoords) -> Vec<PosCoords> {
+fn get_ne_moves(pos: PosCoords, dims: PosCoords, topology: Topology) -> Vec<PosCoords> {
+    if topology == Topology::Toroidal {
+        return get_wrapped_moves(pos, dims, (1, 1));
+    }
     let dis_to_edge = min(dims.0 - pos.0, dims.1 - pos.1);
     (0..dis_to_edge)
         .map(|delta| (pos.0 + delta, pos.1 + delta))
@@ -72,7 +98,10 @@ fn get_ne_moves(pos: PosCoords, dims: PosCoords) -> Vec<PosCoords> {
 
 /// This function will return a set of the possible diagonal moves
 /// going down and to the left.
-fn get_sw_moves(pos: PosCoords, _: PosCoords) -> Vec<PosCoords> {
+fn get_sw_moves(pos: PosCoords, dims: PosCoords, topology: Topology) -> Vec<PosCoords> {
+    if topology == Topology::Toroidal {
+        return get_wrapped_moves(pos, dims, (-1, -1));
+    }
     let dis_to_edge = min(pos.0 + 1, pos.1 + 1);
     (0..dis_to_edge)
         .map(|delta| (pos.0 - delta, pos.1 - delta))
@@ -81,18 +110,36 @@ fn get_sw_moves(pos: PosCoords, _: PosCoords) -> Vec<PosCoords> {
 
 /// This function will return a set of the possible diagonal moves
 /// going down and to the right.
-fn get_se_moves(pos: PosCoords, dims: PosCoords) -> Vec<PosCoords> {
+fn get_se_moves(pos: PosCoords, dims: PosCoords, topology: Topology) -> Vec<PosCoords> {
+    if topology == Topology::Toroidal {
+        return get_wrapped_moves(pos, dims, (1, -1));
+    }
     let dis_to_edge = min(dims.0 - pos.0, pos.1 + 1);
     (0..dis_to_edge)
         .map(|delta| (pos.0 + delta, pos.1 - delta))
         .collect()
 }
 
+/// This function will return the diagonal moves in the given direction on a
+/// toroidal board, wrapping around modulo the dimensions of the board until
+/// the diagonal returns to the queen. This takes the least common multiple
+/// of the dimensions.
+fn get_wrapped_moves(pos: PosCoords, dims: PosCoords, (dx, dy): (i64, i64)) -> Vec<PosCoords> {
+    let period = match dims {
+        (0, _) | (_, 0) => 0,
+        (width, height) => width / gcd(width, height) * height,
+    };
+    (0..period as i64)
+        .filter_map(|delta| Topology::Toroidal.step(pos, dims, (delta * dx, delta * dy)))
+        .collect()
+}
+
 #[cfg(test)]
 mod queens_tests {
-    use super::{get_contested_spaces, get_queen_moves};
+    use super::{get_contested_spaces, get_queen_moves, queens_conflict};
+    use position::CoordIter;
     use position_types::*;
-    use Board;
+    use {Board, Piece, Topology};
 
     #[test]
     fn add_queen_works() {
@@ -146,7 +193,7 @@ mod queens_tests {
             (7, 2),
             (7, 7),
         ];
-        let result = get_queen_moves(pos, dims);
+        let result = get_queen_moves(pos, &Board::from(dims));
         assert_eq!(result, expected);
     }
 
@@ -189,8 +236,108 @@ mod queens_tests {
             (7, 6),
             (7, 7),
         ];
-        let result = get_queen_moves(pos, dims);
+        let result = get_queen_moves(pos, &Board::from(dims));
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn queens_conflict_works() {
+        let b = Board::new();
+        assert!(queens_conflict((2, 2), (2, 7), &b));
+        assert!(queens_conflict((2, 2), (6, 2), &b));
+        assert!(queens_conflict((2, 2), (0, 4), &b));
+        assert!(queens_conflict((2, 2), (7, 7), &b));
+        assert!(!queens_conflict((2, 2), (3, 4), &b));
+        assert!(!queens_conflict((0, 0), (7, 2), &b));
+    }
+
+    ///   01234
+    ///   -----
+    /// 4|x x #|
+    /// 3| xxx |
+    /// 2|xxQx#|
+    /// 1| x#x |
+    /// 0|x   x|
+    ///   -----
+    #[test]
+    fn blocked_squares_stop_queen_moves() {
+        let b = Board::with_blocked((5, 5), vec![(2, 1), (4, 2), (4, 4)]);
+        let expected: CoordList = vec![
+            (0, 0),
+            (0, 2),
+            (0, 4),
+            (1, 1),
+            (1, 2),
+            (1, 3),
+            (2, 2),
+            (2, 3),
+            (2, 4),
+            (3, 1),
+            (3, 2),
+            (3, 3),
+            (4, 0),
+        ];
+        let result = get_queen_moves((2, 2), &b);
+        assert_eq!(result, expected);
+        let contested = get_contested_spaces(vec![(2, 2)], &b);
+        assert_eq!(contested, expected);
+    }
+
+    ///   01234
+    ///   -----
+    /// 4|xxxxQ|
+    /// 3|x  xx|
+    /// 2| xx x|
+    /// 1| xx x|
+    /// 0|x  xx|
+    ///   -----
+    #[test]
+    fn toroidal_diagonals_wrap_around() {
+        let expected: CoordList = vec![
+            (0, 0),
+            (0, 3),
+            (0, 4),
+            (1, 1),
+            (1, 2),
+            (1, 4),
+            (2, 1),
+            (2, 2),
+            (2, 4),
+            (3, 0),
+            (3, 3),
+            (3, 4),
+            (4, 0),
+            (4, 1),
+            (4, 2),
+            (4, 3),
+            (4, 4),
+        ];
+        let mut b = Board::from((5, 5));
+        b.set_topology(Topology::Toroidal);
+        let result = get_queen_moves((4, 4), &b);
         assert_eq!(result, expected);
+        for &dims in [(5, 5), (4, 6), (3, 7)].iter() {
+            let mut b = Board::from(dims);
+            b.set_topology(Topology::Toroidal);
+            for pos in CoordIter::from(dims) {
+                let result = get_queen_moves(pos, &b);
+                let expected = Piece::Queen.get_moves(pos, dims, &[], Topology::Toroidal);
+                assert_eq!(result, expected, "{:?} {:?}", dims, pos);
+            }
+        }
+    }
+
+    #[test]
+    fn blocked_squares_prevent_conflicts() {
+        let b = Board::with_blocked((6, 6), vec![(2, 1), (4, 2)]);
+        assert!(!queens_conflict((2, 0), (2, 4), &b));
+        assert!(queens_conflict((2, 2), (2, 4), &b));
+        assert!(!queens_conflict((3, 2), (5, 2), &b));
+        assert!(queens_conflict((3, 2), (0, 2), &b));
+        assert!(!queens_conflict((1, 0), (3, 2), &b));
+        assert!(!queens_conflict((3, 1), (5, 3), &b));
+        assert!(queens_conflict((3, 0), (5, 2), &b));
+        assert!(!queens_conflict((0, 0), (1, 2), &b));
     }
 
     ///   01234567
@@ -244,7 +391,7 @@ mod queens_tests {
             (7, 6),
             (7, 7),
         ];
-        let result = get_contested_spaces(queen_positions.to_vec(), dims);
+        let result = get_contested_spaces(queen_positions.to_vec(), &Board::from(dims));
         assert_eq!(result, expected);
     }
 }
@@ -256,16 +403,20 @@ mod get_queen_moves_benches {
     use self::test::Bencher;
     use queen::get_queen_moves;
     use rand::Rng;
+    use random::seeded_rng;
+    use Board;
+
+    const RNG_SEED: u64 = 2;
 
     #[bench]
     fn get_queen_moves_bench(bencher: &mut Bencher) {
-        let mut rng = rand::thread_rng();
+        let mut rng = seeded_rng(RNG_SEED);
         let x = rng.gen_range::<u32>(0, 8);
         let y = rng.gen_range::<u32>(0, 8);
         let pos = (x, y);
-        let dims = (8, 8);
+        let board = Board::new();
         bencher.iter(|| {
-            let _ = get_queen_moves(pos, dims);
+            let _ = get_queen_moves(pos, &board);
         });
     }
 }
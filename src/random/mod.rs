@@ -0,0 +1,61 @@
+use rand::{SeedableRng, XorShiftRng};
+
+mod random_board;
+
+pub use self::random_board::RandomBoard;
+
+/// The random number generator created from a seed by `seeded_rng`. Any
+/// other `Rng` can also be passed to the APIs that accept one.
+pub type SeededRng = XorShiftRng;
+
+/// Create a random number generator from a seed. The same seed always
+/// produces the same sequence of numbers, so that randomized boards,
+/// searches, and tests can be reproduced.
+pub fn seeded_rng(seed: u64) -> SeededRng {
+    let mut state = seed;
+    let mut words = [0u32; 4];
+    for word in words.iter_mut() {
+        *word = split_mix(&mut state) as u32;
+    }
+    // A xorshift generator must not be seeded with zeros.
+    if words.iter().all(|&word| word == 0) {
+        words[0] = 1;
+    }
+    SeededRng::from_seed(words)
+}
+
+/// Advance a SplitMix64 generator, which is used to spread the bits of a
+/// seed across the state of the seeded generator.
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod seeded_rng_tests {
+    use super::seeded_rng;
+    use rand::Rng;
+
+    #[test]
+    fn same_seed_produces_same_numbers() {
+        let mut rng_a = seeded_rng(42);
+        let mut rng_b = seeded_rng(42);
+        let a = (0..16).map(|_| rng_a.next_u32()).collect::<Vec<u32>>();
+        let b = (0..16).map(|_| rng_b.next_u32()).collect::<Vec<u32>>();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn different_seeds_produce_different_numbers() {
+        let a = (0..16)
+            .map(|seed| seeded_rng(seed).next_u64())
+            .collect::<Vec<u64>>();
+        let mut sorted = a.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), a.len());
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use super::{seeded_rng, SeededRng};
+use position::CoordIter;
+use solver::MinConflictsSolver;
+use {Board, CoordList, PosCoords};
+
+/// The number of times to try placing non-attacking queens one at a time,
+/// before falling back to removing queens from a full solution.
+const MAX_PLACEMENT_ATTEMPTS: u32 = 64;
+
+/// The number of random squares to try when placing each non-attacking queen.
+const MAX_SQUARE_TRIES: u32 = 256;
+
+/// Generates random boards with a given dimension. Boards are generated
+/// using the given random number generator, so a generator created from a
+/// seed produces the same sequence of boards every time.
+#[derive(Clone, Debug)]
+pub struct RandomBoard<R: Rng = SeededRng> {
+    dims: PosCoords,
+    rng: R,
+}
+
+impl RandomBoard<SeededRng> {
+    /// Create a generator for boards with the given dimensions, using a
+    /// random number generator created from the given seed.
+    pub fn from_seed(dims: PosCoords, seed: u64) -> RandomBoard<SeededRng> {
+        RandomBoard::with_rng(dims, seeded_rng(seed))
+    }
+}
+
+impl<R: Rng> RandomBoard<R> {
+    /// Create a generator for boards with the given dimensions, using the
+    /// given random number generator.
+    pub fn with_rng(dims: PosCoords, rng: R) -> RandomBoard<R> {
+        RandomBoard { dims, rng }
+    }
+
+    /// Generate a board holding `k` queens on distinct squares, which may
+    /// attack one another. Returns `None` if the board has fewer than `k`
+    /// squares.
+    pub fn arbitrary(&mut self, k: u32) -> Option<Board> {
+        let num_squares = self.dims.0 as u64 * self.dims.1 as u64;
+        if k as u64 > num_squares {
+            return None;
+        }
+        let queens = if k as u64 * 2 > num_squares {
+            let mut squares = CoordIter::from(self.dims).collect::<CoordList>();
+            self.rng.shuffle(&mut squares);
+            squares.truncate(k as usize);
+            squares
+        } else {
+            let mut queens = HashSet::new();
+            while queens.len() < k as usize {
+                queens.insert(self.get_random_square());
+            }
+            queens.into_iter().collect()
+        };
+        Some(Board::with_queens(self.dims, queens))
+    }
+
+    /// Generate a board holding `k` queens, none of which attack each other.
+    /// Queens are placed one at a time on random safe squares. If this
+    /// repeatedly fails, `k` queens are chosen from a solution found using
+    /// `MinConflictsSolver` for the largest square that fits on the board,
+    /// placed at a random offset along the longer side of a rectangular
+    /// board. Returns `None` immediately if `k` is larger than the number of
+    /// rows or columns, as each queen needs a row and a column of its own,
+    /// and returns `None` if no arrangement was found, as for three queens
+    /// on a 3x3 board.
+    pub fn non_attacking(&mut self, k: u32) -> Option<Board> {
+        let side = self.dims.0.min(self.dims.1);
+        if k > side {
+            return None;
+        }
+        for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+            if let Some(queens) = self.try_place_non_attacking(k) {
+                return Some(Board::with_queens(self.dims, queens));
+            }
+        }
+        let solution = MinConflictsSolver::new(side).solve_with_rng(&mut self.rng)?;
+        let offset = self.rng.gen_range(0, self.dims.0.max(self.dims.1) - side + 1);
+        let mut queens = solution
+            .get_queen_positions()
+            .into_iter()
+            .map(|(x, y)| match self.dims.0 > self.dims.1 {
+                true => (x + offset, y),
+                false => (x, y + offset),
+            })
+            .collect::<CoordList>();
+        self.rng.shuffle(&mut queens);
+        queens.truncate(k as usize);
+        Some(Board::with_queens(self.dims, queens))
+    }
+
+    /// Try to place `k` non-attacking queens one at a time, on random
+    /// squares that are not attacked by the queens placed so far.
+    fn try_place_non_attacking(&mut self, k: u32) -> Option<CoordList> {
+        let mut queens = CoordList::with_capacity(k as usize);
+        let mut lines: HashSet<(u8, i64)> = HashSet::new();
+        for _ in 0..k {
+            let pos = (0..MAX_SQUARE_TRIES)
+                .map(|_| self.get_random_square())
+                .find(|&pos| get_lines(pos).iter().all(|line| !lines.contains(line)))?;
+            lines.extend(get_lines(pos).iter().cloned());
+            queens.push(pos);
+        }
+        Some(queens)
+    }
+
+    fn get_random_square(&mut self) -> PosCoords {
+        let x = self.rng.gen_range(0, self.dims.0);
+        let y = self.rng.gen_range(0, self.dims.1);
+        (x, y)
+    }
+}
+
+/// Return the column, row, diagonal, and anti-diagonal of a square, tagged so
+/// that they can be stored in a single set.
+fn get_lines((x, y): PosCoords) -> [(u8, i64); 4] {
+    let (x, y) = (x as i64, y as i64);
+    [(0, x), (1, y), (2, x - y), (3, x + y)]
+}
+
+#[cfg(test)]
+mod random_board_tests {
+    use super::RandomBoard;
+    use checker::check_board;
+    use random::seeded_rng;
+
+    #[test]
+    fn same_seed_produces_same_boards() {
+        let mut a = RandomBoard::from_seed((8, 8), 1234);
+        let mut b = RandomBoard::from_seed((8, 8), 1234);
+        for k in 0..8 {
+            assert_eq!(a.arbitrary(k), b.arbitrary(k));
+            assert_eq!(a.non_attacking(k), b.non_attacking(k));
+        }
+    }
+
+    #[test]
+    fn arbitrary_boards_hold_k_queens() {
+        let mut generator = RandomBoard::from_seed((5, 3), 1);
+        for &k in [0, 4, 8, 15].iter() {
+            let board = generator.arbitrary(k).unwrap();
+            assert_eq!(board.dims(), (5, 3));
+            assert_eq!(board.get_queen_positions().len(), k as usize);
+        }
+        assert_eq!(generator.arbitrary(16), None);
+    }
+
+    #[test]
+    fn non_attacking_boards_have_no_conflicts() {
+        let mut generator = RandomBoard::with_rng((8, 8), seeded_rng(2));
+        for k in 0..9 {
+            let board = generator.non_attacking(k).unwrap();
+            let res = check_board(&board);
+            assert_eq!(res.num_queens, k);
+            assert!(!res.has_conflict);
+        }
+        assert_eq!(generator.non_attacking(9), None);
+    }
+
+    #[test]
+    fn non_attacking_boards_can_fill_large_boards() {
+        let mut generator = RandomBoard::from_seed((100, 100), 3);
+        let board = generator.non_attacking(100).unwrap();
+        assert!(check_board(&board).is_solved);
+        let mut generator = RandomBoard::from_seed((40, 10), 3);
+        let board = generator.non_attacking(5).unwrap();
+        assert!(!check_board(&board).has_conflict);
+    }
+
+    #[test]
+    fn non_attacking_boards_can_be_rectangular() {
+        for &dims in [(12, 5), (5, 12), (30, 20)].iter() {
+            let side = dims.0.min(dims.1);
+            let mut generator = RandomBoard::from_seed(dims, 4);
+            for k in 0..side + 1 {
+                let board = generator.non_attacking(k).unwrap();
+                let res = check_board(&board);
+                assert_eq!(board.dims(), dims);
+                assert_eq!(res.num_queens, k);
+                assert!(!res.has_conflict);
+            }
+            assert_eq!(generator.non_attacking(side + 1), None);
+        }
+        assert_eq!(RandomBoard::from_seed((3, 3), 4).non_attacking(3), None);
+    }
+}
@@ -0,0 +1,391 @@
+use std::collections::HashSet;
+
+use checker::get_conflicting_queens;
+use {Board, PosCoords};
+
+/// The style of the labels drawn along the axes of a board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AxisLabels {
+    /// Do not draw any labels.
+    #[default]
+    None,
+    /// Label the rows and columns with their coordinates, as in the diagrams
+    /// used throughout the tests. Only the last digit of each column is drawn.
+    Numeric,
+    /// Label the files with letters, and the ranks with numbers starting at 1,
+    /// as on a chess board. Files after 'z' are named 'aa', 'ab', and so on.
+    Chess,
+}
+
+/// The characters used to draw a board.
+struct Glyphs {
+    empty: char,
+    dark: char,
+    contested: char,
+    conflict: char,
+    blocked: char,
+    horizontal: char,
+    vertical: char,
+    corners: [char; 4],
+}
+
+static ASCII_GLYPHS: Glyphs = Glyphs {
+    empty: ' ',
+    dark: '.',
+    contested: 'x',
+    conflict: '!',
+    blocked: '#',
+    horizontal: '-',
+    vertical: '|',
+    corners: ['-', '-', '-', '-'],
+};
+
+static UNICODE_GLYPHS: Glyphs = Glyphs {
+    empty: ' ',
+    dark: '░',
+    contested: '×',
+    conflict: '!',
+    blocked: '█',
+    horizontal: '─',
+    vertical: '│',
+    corners: ['┌', '┐', '└', '┘'],
+};
+
+/// Renders a board as text. The default renderer produces the same output
+/// as `Board::to_string`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BoardRenderer {
+    /// The labels drawn along the axes of the board.
+    pub axis_labels: AxisLabels,
+    /// Mark the empty squares that are contested by a piece.
+    pub show_contested: bool,
+    /// Draw the pieces that are in conflict with another piece differently.
+    pub highlight_conflicts: bool,
+    /// Use Unicode chess glyphs and box-drawing characters. Pieces are drawn
+    /// using `Piece::glyph`, rather than `Piece::symbol`, and conflicting
+    /// pieces using `Piece::outline_glyph`, where there is one.
+    pub unicode: bool,
+    /// Shade the empty dark squares, with (0, 0) being a dark square.
+    pub checkerboard: bool,
+}
+
+impl BoardRenderer {
+    /// Render the board, with the highest row at the top.
+    pub fn render(&self, board: &Board) -> String {
+        let glyphs = self.get_glyphs();
+        let (width, height) = board.dims();
+        let label_width = self.get_row_label_width(height);
+        let indent = " ".repeat(label_width);
+        let contested = self.get_contested(board);
+        let conflicts = self.get_conflicts(board);
+        let piece = match self.unicode {
+            true => board.piece().glyph(),
+            false => board.piece().symbol(),
+        };
+        let conflict = match self.unicode {
+            true => board.piece().outline_glyph().unwrap_or(glyphs.conflict),
+            false => glyphs.conflict,
+        };
+        let queens = board
+            .get_queen_positions()
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let mut lines = Vec::with_capacity(height as usize + 4);
+        if self.axis_labels == AxisLabels::Numeric {
+            for labels in self.get_column_labels(width) {
+                lines.push(format!("{} {}", indent, labels));
+            }
+        }
+        lines.push(self.form_horizontal_border(
+            &indent,
+            width,
+            glyphs.corners[0],
+            glyphs.corners[1],
+        ));
+        for y in (0..height).rev() {
+            let squares = (0..width)
+                .map(|x| {
+                    let pos = (x, y);
+                    if conflicts.contains(&pos) {
+                        conflict
+                    } else if queens.contains(&pos) {
+                        piece
+                    } else if board.is_blocked(&pos) {
+                        glyphs.blocked
+                    } else if contested.contains(&pos) {
+                        glyphs.contested
+                    } else if self.checkerboard && (x + y) % 2 == 0 {
+                        glyphs.dark
+                    } else {
+                        glyphs.empty
+                    }
+                })
+                .collect::<String>();
+            let label = self.get_row_label(y, label_width);
+            lines.push(format!(
+                "{}{}{}{}",
+                label, glyphs.vertical, squares, glyphs.vertical
+            ));
+        }
+        lines.push(self.form_horizontal_border(
+            &indent,
+            width,
+            glyphs.corners[2],
+            glyphs.corners[3],
+        ));
+        if self.axis_labels == AxisLabels::Chess {
+            for labels in self.get_column_labels(width) {
+                lines.push(format!("{} {}", indent, labels));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn get_glyphs(&self) -> &'static Glyphs {
+        match self.unicode {
+            true => &UNICODE_GLYPHS,
+            false => &ASCII_GLYPHS,
+        }
+    }
+
+    fn get_contested(&self, board: &Board) -> HashSet<PosCoords> {
+        match self.show_contested {
+            true => board
+                .piece()
+                .get_contested_spaces(
+                    &board.get_queen_positions(),
+                    board.dims(),
+                    &board.get_blocked_positions(),
+                    board.topology(),
+                )
+                .into_iter()
+                .collect(),
+            false => HashSet::new(),
+        }
+    }
+
+    fn get_conflicts(&self, board: &Board) -> HashSet<PosCoords> {
+        match self.highlight_conflicts {
+            true => get_conflicting_queens(board).into_iter().collect(),
+            false => HashSet::new(),
+        }
+    }
+
+    /// Return the width of the widest row label.
+    fn get_row_label_width(&self, height: u32) -> usize {
+        match self.axis_labels {
+            AxisLabels::None => 0,
+            AxisLabels::Numeric => height.saturating_sub(1).to_string().len(),
+            AxisLabels::Chess => height.to_string().len(),
+        }
+    }
+
+    fn get_row_label(&self, y: u32, label_width: usize) -> String {
+        match self.axis_labels {
+            AxisLabels::None => String::new(),
+            AxisLabels::Numeric => format!("{:>1$}", y, label_width),
+            AxisLabels::Chess => format!("{:>1$}", y + 1, label_width),
+        }
+    }
+
+    /// Return the lines of labels for the columns. Numeric labels only show
+    /// the last digit of each column. Chess files are named as spreadsheet
+    /// columns are, with 'aa' following 'z', and each name is written
+    /// downwards, one letter per line.
+    fn get_column_labels(&self, width: u32) -> Vec<String> {
+        match self.axis_labels {
+            AxisLabels::Chess => {
+                let files = (0..width).map(get_file_name).collect::<Vec<String>>();
+                let num_lines = files.iter().map(String::len).max().unwrap_or(0);
+                (0..num_lines)
+                    .map(|i| {
+                        files
+                            .iter()
+                            .map(|file| file.chars().nth(i).unwrap_or(' '))
+                            .collect()
+                    })
+                    .collect()
+            }
+            _ => vec![(0..width).map(|x| (b'0' + (x % 10) as u8) as char).collect()],
+        }
+    }
+
+    fn form_horizontal_border(&self, indent: &str, width: u32, left: char, right: char) -> String {
+        let line = self
+            .get_glyphs()
+            .horizontal
+            .to_string()
+            .repeat(width as usize);
+        format!("{}{}{}{}", indent, left, line, right)
+    }
+}
+
+/// Return the name of the file with the given index, using the letters 'a'
+/// to 'z', followed by 'aa', 'ab', and so on.
+fn get_file_name(x: u32) -> String {
+    let mut letters = Vec::new();
+    let mut n = x + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters.into_iter().rev().collect()
+}
+
+#[cfg(test)]
+mod board_renderer_tests {
+    use super::{get_file_name, AxisLabels, BoardRenderer};
+    use std::string::ToString;
+    use {Board, Piece};
+
+    #[test]
+    fn default_renderer_matches_to_string() {
+        let b = Board::with_queens((5, 3), vec![(0, 0), (4, 2)]);
+        assert_eq!(BoardRenderer::default().render(&b), b.to_string());
+        let b = Board::new();
+        assert_eq!(BoardRenderer::default().render(&b), b.to_string());
+    }
+
+    /// Compare against the diagram in `queen::queens_tests`.
+    #[test]
+    fn numeric_labels_and_contested_squares_are_rendered() {
+        let b = Board::with_queens((8, 8), vec![(2, 2)]);
+        let renderer = BoardRenderer {
+            axis_labels: AxisLabels::Numeric,
+            show_contested: true,
+            ..BoardRenderer::default()
+        };
+        let expected = [
+            "  01234567",
+            " ----------",
+            "7|  x    x|",
+            "6|  x   x |",
+            "5|  x  x  |",
+            "4|x x x   |",
+            "3| xxx    |",
+            "2|xxQxxxxx|",
+            "1| xxx    |",
+            "0|x x x   |",
+            " ----------",
+        ]
+        .join("\n");
+        assert_eq!(renderer.render(&b), expected);
+    }
+
+    #[test]
+    fn blocked_squares_stop_contested_squares() {
+        let mut b = Board::with_blocked((4, 3), vec![(2, 0), (1, 1)]);
+        b.add_queen((0, 0));
+        let renderer = BoardRenderer {
+            show_contested: true,
+            ..BoardRenderer::default()
+        };
+        let expected = ["------", "|x   |", "|x#  |", "|Qx# |", "------"].join("\n");
+        assert_eq!(renderer.render(&b), expected);
+        let renderer = BoardRenderer {
+            unicode: true,
+            ..BoardRenderer::default()
+        };
+        let expected = ["┌────┐", "│    │", "│ █  │", "│♛ █ │", "└────┘"].join("\n");
+        assert_eq!(renderer.render(&b), expected);
+    }
+
+    #[test]
+    fn pieces_are_rendered_with_their_symbols() {
+        let mut b = Board::with_queens((3, 3), vec![(0, 0)]);
+        b.set_piece(Piece::Knight);
+        let renderer = BoardRenderer {
+            show_contested: true,
+            ..BoardRenderer::default()
+        };
+        let expected = ["-----", "| x |", "|  x|", "|N  |", "-----"].join("\n");
+        assert_eq!(renderer.render(&b), expected);
+        let renderer = BoardRenderer {
+            unicode: true,
+            ..BoardRenderer::default()
+        };
+        let expected = ["┌───┐", "│   │", "│   │", "│♞  │", "└───┘"].join("\n");
+        assert_eq!(renderer.render(&b), expected);
+    }
+
+    #[test]
+    fn chess_labels_are_rendered() {
+        let b = Board::with_queens((3, 10), vec![(1, 9)]);
+        let renderer = BoardRenderer {
+            axis_labels: AxisLabels::Chess,
+            ..BoardRenderer::default()
+        };
+        let lines = renderer
+            .render(&b)
+            .lines()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(lines[0], "  -----");
+        assert_eq!(lines[1], "10| Q |");
+        assert_eq!(lines[10], " 1|   |");
+        assert_eq!(lines[12], "   abc");
+    }
+
+    /// Files after 'z' are named 'aa', 'ab', and so on, written downwards
+    /// over two lines.
+    #[test]
+    fn chess_labels_continue_after_z() {
+        let b = Board::from((28, 1));
+        let renderer = BoardRenderer {
+            axis_labels: AxisLabels::Chess,
+            ..BoardRenderer::default()
+        };
+        let lines = renderer
+            .render(&b)
+            .lines()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[3], "  abcdefghijklmnopqrstuvwxyzaa");
+        assert_eq!(lines[4], "                            ab");
+        assert_eq!(get_file_name(701), "zz");
+        assert_eq!(get_file_name(702), "aaa");
+    }
+
+    #[test]
+    fn conflicting_queens_are_highlighted() {
+        let b = Board::with_queens((4, 2), vec![(0, 0), (1, 0), (3, 1)]);
+        let renderer = BoardRenderer {
+            highlight_conflicts: true,
+            ..BoardRenderer::default()
+        };
+        let expected = ["------", "|   Q|", "|!!  |", "------"].join("\n");
+        assert_eq!(renderer.render(&b), expected);
+    }
+
+    #[test]
+    fn unicode_checkerboard_is_rendered() {
+        let b = Board::with_queens((3, 3), vec![(0, 0), (1, 1)]);
+        let renderer = BoardRenderer {
+            unicode: true,
+            checkerboard: true,
+            highlight_conflicts: true,
+            ..BoardRenderer::default()
+        };
+        let expected = ["┌───┐", "│░ ░│", "│ ♕ │", "│♕ ░│", "└───┘"].join("\n");
+        assert_eq!(renderer.render(&b), expected);
+    }
+
+    #[test]
+    fn conflicting_pieces_are_drawn_with_their_glyphs() {
+        let mut b = Board::with_queens((3, 3), vec![(0, 0), (1, 1), (2, 1)]);
+        b.set_piece(Piece::King);
+        let renderer = BoardRenderer {
+            unicode: true,
+            highlight_conflicts: true,
+            ..BoardRenderer::default()
+        };
+        let expected = ["┌───┐", "│   │", "│ ♔♔│", "│♔  │", "└───┘"].join("\n");
+        assert_eq!(renderer.render(&b), expected);
+        b.set_piece(Piece::Amazon);
+        let expected = ["┌───┐", "│   │", "│ !!│", "│!  │", "└───┘"].join("\n");
+        assert_eq!(renderer.render(&b), expected);
+    }
+}
@@ -0,0 +1,5 @@
+mod board_renderer;
+mod svg_renderer;
+
+pub use self::board_renderer::{AxisLabels, BoardRenderer};
+pub use self::svg_renderer::SvgRenderer;
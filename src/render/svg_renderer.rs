@@ -0,0 +1,361 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use checker::get_conflicting_queens;
+use piece::Line;
+use solver::SolutionSet;
+use {Board, PosCoords, Topology};
+
+static LIGHT_SQUARE_COLOR: &str = "#f0d9b5";
+static DARK_SQUARE_COLOR: &str = "#b58863";
+static QUEEN_COLOR: &str = "#222222";
+static CONFLICT_COLOR: &str = "#d03030";
+static RAY_COLOR: &str = "#3070d0";
+static BLOCKED_COLOR: &str = "#404040";
+
+/// Renders a board, or a set of solutions, as an SVG image. Squares are
+/// shaded as on a chess board, with (0, 0) being a dark square in the
+/// bottom-left corner. Blocked squares are filled in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SvgRenderer {
+    /// The width and height of each square, in pixels.
+    pub square_size: u32,
+    /// Draw a line from each piece to the furthest square it contests in
+    /// each direction that it slides along.
+    pub show_attack_rays: bool,
+    /// Draw the pieces that are in conflict with another piece in red, on a
+    /// highlighted square.
+    pub highlight_conflicts: bool,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> SvgRenderer {
+        SvgRenderer {
+            square_size: 40,
+            show_attack_rays: false,
+            highlight_conflicts: false,
+        }
+    }
+}
+
+impl SvgRenderer {
+    /// Render the board as an SVG document.
+    pub fn render(&self, board: &Board) -> String {
+        let (width, height) = self.get_board_size(board);
+        let mut svg = self.form_header(width, height);
+        self.push_board(&mut svg, board, (0, 0));
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Render a set of solutions as an SVG document, laying the boards out
+    /// in a grid with the given number of columns. Each board is captioned
+    /// with its position in the set.
+    pub fn render_gallery(&self, solutions: &SolutionSet, columns: usize) -> String {
+        let boards = solutions.boards();
+        let columns = columns.max(1).min(boards.len().max(1));
+        let rows = boards.len().div_ceil(columns);
+        let spacing = self.square_size / 2;
+        let caption_height = self.square_size;
+        let (board_width, board_height) = match boards.first() {
+            Some(board) => self.get_board_size(board),
+            None => (0, 0),
+        };
+        let cell_width = board_width + spacing;
+        let cell_height = board_height + caption_height + spacing;
+        let mut svg = self.form_header(
+            cell_width * columns as u32 + spacing,
+            cell_height * rows as u32 + spacing,
+        );
+        for (i, board) in boards.iter().enumerate() {
+            let x = spacing + cell_width * (i % columns) as u32;
+            let y = spacing + cell_height * (i / columns) as u32;
+            self.push_board(&mut svg, board, (x, y));
+            svg.push_str(&format!(
+                "<text class=\"caption\" x=\"{}\" y=\"{}\" font-size=\"{}\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\">Solution {} of {}</text>\n",
+                x + board_width / 2,
+                y + board_height + caption_height / 2,
+                self.square_size * 2 / 5,
+                i + 1,
+                boards.len(),
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Return the size of the board in pixels.
+    fn get_board_size(&self, board: &Board) -> PosCoords {
+        (
+            board.width() * self.square_size,
+            board.height() * self.square_size,
+        )
+    }
+
+    fn form_header(&self, width: u32, height: u32) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\">\n",
+            width, height
+        )
+    }
+
+    /// Push the elements used to draw a board, with its top-left corner at
+    /// the given offset in pixels.
+    fn push_board(&self, svg: &mut String, board: &Board, offset: PosCoords) {
+        let conflicts = match self.highlight_conflicts {
+            true => get_conflicting_queens(board).into_iter().collect(),
+            false => BTreeSet::new(),
+        };
+        svg.push_str(&format!(
+            "<g transform=\"translate({}, {})\">\n",
+            offset.0, offset.1
+        ));
+        for y in (0..board.height()).rev() {
+            for x in 0..board.width() {
+                let (class, color) = if board.is_blocked(&(x, y)) {
+                    ("square blocked", BLOCKED_COLOR)
+                } else if (x + y) % 2 == 0 {
+                    ("square dark", DARK_SQUARE_COLOR)
+                } else {
+                    ("square light", LIGHT_SQUARE_COLOR)
+                };
+                let (px, py) = self.get_square_origin(board, (x, y));
+                svg.push_str(&format!(
+                    "<rect class=\"{}\" x=\"{}\" y=\"{}\" width=\"{3}\" height=\"{3}\" \
+                     fill=\"{4}\"/>\n",
+                    class, px, py, self.square_size, color
+                ));
+            }
+        }
+        for &pos in conflicts.iter() {
+            let (px, py) = self.get_square_origin(board, pos);
+            svg.push_str(&format!(
+                "<rect class=\"conflict\" x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\" \
+                 fill=\"{3}\" fill-opacity=\"0.5\"/>\n",
+                px, py, self.square_size, CONFLICT_COLOR
+            ));
+        }
+        if self.show_attack_rays {
+            self.push_attack_rays(svg, board);
+        }
+        for pos in board.get_queen_positions() {
+            let (cx, cy) = self.get_square_center(board, pos);
+            let (class, color) = match conflicts.contains(&pos) {
+                true => ("queen conflict", CONFLICT_COLOR),
+                false => ("queen", QUEEN_COLOR),
+            };
+            svg.push_str(&format!(
+                "<text class=\"{}\" x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                class,
+                cx,
+                cy,
+                self.square_size * 4 / 5,
+                color,
+                board.piece().glyph()
+            ));
+        }
+        svg.push_str("</g>\n");
+    }
+
+    /// Push a line from each piece to the furthest square that it contests
+    /// in each direction, such as the eight directions of a queen. Rays stop
+    /// before blocked squares, and are not drawn for a knight's leaps. Rays
+    /// are drawn within the board, so they do not wrap around the edges of a
+    /// toroidal board.
+    fn push_attack_rays(&self, svg: &mut String, board: &Board) {
+        let blocked = board.get_blocked_positions();
+        for queen in board.get_queen_positions() {
+            let mut ends: BTreeMap<(i64, i64), PosCoords> = BTreeMap::new();
+            let moves = board
+                .piece()
+                .get_moves(queen, board.dims(), &blocked, Topology::Flat);
+            for pos in moves.into_iter().filter(|&pos| pos != queen) {
+                let dx = pos.0 as i64 - queen.0 as i64;
+                let dy = pos.1 as i64 - queen.1 as i64;
+                if Line::between((dx, dy)).is_none() {
+                    continue;
+                }
+                let end = ends.entry((dx.signum(), dy.signum())).or_insert(pos);
+                if dx.abs().max(dy.abs()) > distance(queen, *end) {
+                    *end = pos;
+                }
+            }
+            let (x1, y1) = self.get_square_center(board, queen);
+            for end in ends.values() {
+                let (x2, y2) = self.get_square_center(board, *end);
+                svg.push_str(&format!(
+                    "<line class=\"ray\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" \
+                     stroke=\"{}\" stroke-width=\"{}\" stroke-opacity=\"0.6\"/>\n",
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    RAY_COLOR,
+                    (self.square_size / 16).max(1)
+                ));
+            }
+        }
+    }
+
+    /// Return the top-left corner of a square. Row 0 is drawn at the bottom.
+    fn get_square_origin(&self, board: &Board, pos: PosCoords) -> PosCoords {
+        let row = board.height() - 1 - pos.1;
+        (pos.0 * self.square_size, row * self.square_size)
+    }
+
+    fn get_square_center(&self, board: &Board, pos: PosCoords) -> PosCoords {
+        let (x, y) = self.get_square_origin(board, pos);
+        (x + self.square_size / 2, y + self.square_size / 2)
+    }
+}
+
+/// Return the number of squares between two positions along a line.
+fn distance(a: PosCoords, b: PosCoords) -> i64 {
+    (a.0 as i64 - b.0 as i64)
+        .abs()
+        .max((a.1 as i64 - b.1 as i64).abs())
+}
+
+#[cfg(test)]
+mod svg_renderer_tests {
+    use super::SvgRenderer;
+    use solver::SolutionSet;
+    use {Board, Piece};
+
+    fn count(svg: &str, pattern: &str) -> usize {
+        svg.matches(pattern).count()
+    }
+
+    #[test]
+    fn board_is_rendered_as_checkerboard() {
+        let b = Board::with_queens((3, 2), vec![(0, 0), (2, 1)]);
+        let svg = SvgRenderer::default().render(&b);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("width=\"120\" height=\"80\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(count(&svg, "class=\"square dark\""), 3);
+        assert_eq!(count(&svg, "class=\"square light\""), 3);
+        assert_eq!(count(&svg, "class=\"queen\""), 2);
+        assert_eq!(count(&svg, "<line"), 0);
+    }
+
+    /// Row 0 is drawn at the bottom of the image.
+    #[test]
+    fn queens_are_drawn_from_the_bottom_row() {
+        let b = Board::with_queens((4, 4), vec![(1, 0)]);
+        let svg = SvgRenderer::default().render(&b);
+        assert!(svg.contains("<text class=\"queen\" x=\"60\" y=\"140\""));
+    }
+
+    #[test]
+    fn attack_rays_reach_the_edges_of_the_board() {
+        let renderer = SvgRenderer {
+            show_attack_rays: true,
+            ..SvgRenderer::default()
+        };
+        let svg = renderer.render(&Board::with_queens((8, 8), vec![(2, 2)]));
+        assert_eq!(count(&svg, "class=\"ray\""), 8);
+        assert!(svg.contains("x1=\"100\" y1=\"220\" x2=\"300\" y2=\"20\""));
+        let svg = renderer.render(&Board::with_queens((8, 8), vec![(0, 0)]));
+        assert_eq!(count(&svg, "class=\"ray\""), 3);
+    }
+
+    #[test]
+    fn blocked_squares_are_filled_and_stop_rays() {
+        let renderer = SvgRenderer {
+            show_attack_rays: true,
+            ..SvgRenderer::default()
+        };
+        let mut b = Board::with_blocked((8, 8), vec![(3, 3), (2, 3)]);
+        b.add_queen((2, 2));
+        let svg = renderer.render(&b);
+        assert_eq!(count(&svg, "class=\"square blocked\""), 2);
+        assert_eq!(count(&svg, "class=\"ray\""), 6);
+        assert!(!svg.contains("x2=\"300\" y2=\"20\""));
+    }
+
+    #[test]
+    fn pieces_draw_their_glyphs_and_rays() {
+        let renderer = SvgRenderer {
+            show_attack_rays: true,
+            ..SvgRenderer::default()
+        };
+        let mut b = Board::with_queens((8, 8), vec![(2, 2)]);
+        b.set_piece(Piece::Rook);
+        let svg = renderer.render(&b);
+        assert!(svg.contains('♜'));
+        assert_eq!(count(&svg, "class=\"ray\""), 4);
+        b.set_piece(Piece::Knight);
+        let svg = renderer.render(&b);
+        assert!(svg.contains('♞'));
+        assert_eq!(count(&svg, "class=\"ray\""), 0);
+    }
+
+    #[test]
+    fn conflicting_queens_are_highlighted() {
+        let renderer = SvgRenderer {
+            highlight_conflicts: true,
+            ..SvgRenderer::default()
+        };
+        let b = Board::with_queens((4, 4), vec![(0, 0), (2, 2), (3, 0)]);
+        let svg = renderer.render(&b);
+        assert_eq!(count(&svg, "class=\"conflict\""), 3);
+        assert_eq!(count(&svg, "class=\"queen conflict\""), 3);
+        let b = Board::with_queens((4, 4), vec![(1, 0), (3, 1)]);
+        let svg = renderer.render(&b);
+        assert_eq!(count(&svg, "conflict"), 0);
+    }
+
+    /// Conflicts are drawn in order of their positions, so that the output
+    /// is the same every time a board is rendered.
+    #[test]
+    fn conflicts_are_drawn_in_a_stable_order() {
+        let renderer = SvgRenderer {
+            highlight_conflicts: true,
+            ..SvgRenderer::default()
+        };
+        let b = Board::with_queens((4, 4), vec![(3, 0), (0, 0), (2, 2)]);
+        let svg = renderer.render(&b);
+        let offsets = [(0, 0), (2, 2), (3, 0)]
+            .iter()
+            .map(|&pos| {
+                let (px, py) = renderer.get_square_origin(&b, pos);
+                svg.find(&format!("class=\"conflict\" x=\"{}\" y=\"{}\"", px, py))
+                    .unwrap()
+            })
+            .collect::<Vec<usize>>();
+        assert!(offsets.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn gallery_lays_out_solutions_in_a_grid() {
+        let solutions = [
+            vec![(0, 1), (1, 3), (2, 0), (3, 2)],
+            vec![(0, 2), (1, 0), (2, 3), (3, 1)],
+        ];
+        let set = SolutionSet::new((4, 4), solutions.iter().cloned().collect());
+        let renderer = SvgRenderer {
+            square_size: 10,
+            ..SvgRenderer::default()
+        };
+        let svg = renderer.render_gallery(&set, 4);
+        assert!(svg.contains("width=\"95\" height=\"60\""));
+        assert_eq!(count(&svg, "<g transform"), 2);
+        assert!(svg.contains("translate(50, 5)"));
+        assert!(svg.contains(">Solution 1 of 2</text>"));
+        assert!(svg.contains(">Solution 2 of 2</text>"));
+        let svg = renderer.render_gallery(&set, 1);
+        assert!(svg.contains("width=\"50\" height=\"115\""));
+        assert!(svg.contains("translate(5, 60)"));
+    }
+
+    #[test]
+    fn empty_gallery_is_rendered() {
+        let set = SolutionSet::new((3, 3), Default::default());
+        let svg = SvgRenderer::default().render_gallery(&set, 4);
+        assert_eq!(count(&svg, "<g transform"), 0);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
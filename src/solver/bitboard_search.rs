@@ -0,0 +1,383 @@
+use super::{SearchMode, Solver};
+use {CoordList, Piece, PosCoords, Topology};
+
+/// The longest side of a board that can be represented by the bitboard search.
+pub const MAX_BITBOARD_WIDTH: u32 = 64;
+
+/// A frame of the bitboard search, representing a single row. The `cols`,
+/// `left`, and `right` masks mark the squares in this row that are contested
+/// along columns and both diagonals by the queens in the rows below it.
+/// `available` holds the squares in this row that have not been tried yet.
+#[derive(Clone, Debug)]
+struct Frame {
+    cols: u64,
+    left: u64,
+    right: u64,
+    available: u64,
+}
+
+/// Row-by-row backtracking search, where the columns and diagonals that are
+/// contested are represented as integer bitmasks rather than coordinate lists.
+/// `fixed` holds a mask for each row, which only contains the starting queen
+/// for rows that had one, and every column otherwise. Boards that are taller
+/// than they are wide are searched column-by-column, by transposing them.
+/// `fresh` is true until the search first advances, and is only set for
+/// searches without starting queens.
+#[derive(Clone, Debug)]
+pub struct BitboardSearch {
+    transposed: bool,
+    fresh: bool,
+    full: u64,
+    fixed: Vec<u64>,
+    placed: Vec<u64>,
+    stack: Vec<Frame>,
+}
+
+impl BitboardSearch {
+    /// Create a new search for a board of the given dimensions, starting from
+    /// the given queen positions. The starting queens are kept fixed in their
+    /// rows. Returns `None` if the board is too large to be represented, or
+    /// if it is empty, as the search places a queen in every row.
+    pub fn new(dims: PosCoords, queen_positions: &[PosCoords]) -> Option<BitboardSearch> {
+        let transposed = dims.1 > dims.0;
+        let (width, height) = if transposed { (dims.1, dims.0) } else { dims };
+        if width > MAX_BITBOARD_WIDTH || height == 0 {
+            return None;
+        }
+        let full = match width {
+            MAX_BITBOARD_WIDTH => !0,
+            _ => (1 << width) - 1,
+        };
+        let mut search = BitboardSearch {
+            transposed,
+            fresh: queen_positions.is_empty(),
+            full,
+            fixed: vec![full; height as usize],
+            placed: vec![0; height as usize],
+            stack: Vec::with_capacity(height as usize),
+        };
+        let mut valid_start = true;
+        for &pos in queen_positions {
+            let (x, y) = if transposed { (pos.1, pos.0) } else { pos };
+            let row = &mut search.fixed[y as usize];
+            if *row != full {
+                valid_start = false;
+            }
+            *row = 1 << x;
+        }
+        if valid_start {
+            let available = search.get_available(0, full);
+            search.stack.push(Frame {
+                cols: 0,
+                left: 0,
+                right: 0,
+                available,
+            });
+        }
+        Some(search)
+    }
+
+    /// Returns true if the search space has been exhausted.
+    pub fn is_done(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Advance the search by trying a single square. Returns true if this
+    /// completed a solution, which can then be read using `get_solution`.
+    pub fn step(&mut self) -> bool {
+        self.fresh = false;
+        let height = self.fixed.len();
+        let row = match self.stack.len() {
+            0 => return false,
+            len => len - 1,
+        };
+        let (cols, left, right, bit) = {
+            let frame = self.stack.last_mut().unwrap();
+            if frame.available == 0 {
+                self.stack.pop();
+                return false;
+            }
+            let bit = frame.available & frame.available.wrapping_neg();
+            frame.available ^= bit;
+            (frame.cols | bit, frame.left | bit, frame.right | bit, bit)
+        };
+        self.placed[row] = bit;
+        if row + 1 == height {
+            return true;
+        }
+        let left = (left << 1) & self.full;
+        let right = right >> 1;
+        let available = self.get_available(row + 1, !(cols | left | right) & self.full);
+        self.stack.push(Frame {
+            cols,
+            left,
+            right,
+            available,
+        });
+        false
+    }
+
+    /// Count the remaining solutions, without storing them.
+    pub fn count_remaining(&mut self) -> u64 {
+        self.fresh = false;
+        let mut count = 0;
+        while let Some(frame) = self.stack.pop() {
+            let row = self.stack.len();
+            count += self.count_from(row, &frame);
+        }
+        count
+    }
+
+    /// Count the solutions of a search that has not started yet, using the
+    /// reflection of the board across its vertical axis. Each solution with
+    /// its first queen in the left half of the first row is reflected onto
+    /// one with its first queen in the right half, so only the left half
+    /// and the middle column are searched. Returns `None` if the search has
+    /// already started, or if it started with fixed queens.
+    pub fn count_mirrored(&mut self) -> Option<u64> {
+        if !self.fresh {
+            return None;
+        }
+        self.fresh = false;
+        let frame = self.stack.pop().unwrap();
+        let width = self.full.count_ones();
+        let left = (1 << (width / 2)) - 1;
+        let middle = match width % 2 {
+            1 => 1 << (width / 2),
+            _ => 0,
+        };
+        let count_with = |available| {
+            self.count_from(
+                0,
+                &Frame {
+                    available,
+                    ..frame.clone()
+                },
+            )
+        };
+        Some(2 * count_with(frame.available & left) + count_with(frame.available & middle))
+    }
+
+    /// Return the queen positions of the most recently completed solution.
+    pub fn get_solution(&self) -> CoordList {
+        let mut queen_positions = self.placed
+            .iter()
+            .enumerate()
+            .map(|(row, bit)| (bit.trailing_zeros(), row as u32))
+            .map(|(x, y)| if self.transposed { (y, x) } else { (x, y) })
+            .collect::<CoordList>();
+        queen_positions.sort();
+        queen_positions
+    }
+
+    /// Recursively count the solutions that can be reached from a frame.
+    fn count_from(&self, row: usize, frame: &Frame) -> u64 {
+        let mut count = 0;
+        let mut available = frame.available;
+        while available != 0 {
+            let bit = available & available.wrapping_neg();
+            available ^= bit;
+            if row + 1 == self.fixed.len() {
+                count += 1;
+                continue;
+            }
+            let cols = frame.cols | bit;
+            let left = ((frame.left | bit) << 1) & self.full;
+            let right = (frame.right | bit) >> 1;
+            let next = Frame {
+                cols,
+                left,
+                right,
+                available: self.get_available(row + 1, !(cols | left | right) & self.full),
+            };
+            count += self.count_from(row + 1, &next);
+        }
+        count
+    }
+
+    /// Restrict the safe squares of a row to its fixed queen, if it has one.
+    fn get_available(&self, row: usize, safe: u64) -> u64 {
+        safe & self.fixed[row]
+    }
+}
+
+impl Solver {
+    /// Tick the solver forward one iteration using the bitboard search. If
+    /// the board is too wide to be represented, holds pieces other than
+    /// queens, or is toroidal, use the exhaustive search.
+    pub(super) fn tick_bitboard(&mut self) {
+        match self.take_bitboard_search() {
+            Some(mut search) => {
+                if search.step() {
+                    self.add_solution(search.get_solution());
+                }
+                if !search.is_done() {
+                    self.bitboard = Some(search);
+                }
+            }
+            None => self.tick_exhaustive(),
+        }
+    }
+
+    /// Take the bitboard search that is in progress, or start a new one from
+    /// the next state on the state heap. Returns `None` if the solver is not
+    /// in bitboard mode, if the board has blocked squares, holds pieces other
+    /// than queens, or is toroidal, or if the board is too large to be
+    /// represented or is empty.
+    pub(super) fn take_bitboard_search(&mut self) -> Option<BitboardSearch> {
+        let is_queens = self.piece == Piece::Queen && self.blocked.is_empty();
+        let is_queens = is_queens && self.topology == Topology::Flat;
+        if self.mode != SearchMode::Bitboard || !is_queens {
+            return None;
+        }
+        if self.bitboard.is_some() {
+            return self.bitboard.take();
+        }
+        let dims = self.dimensions;
+        if dims.0.max(dims.1) > MAX_BITBOARD_WIDTH || dims.0.min(dims.1) == 0 {
+            return None;
+        }
+        self.state_heap
+            .pop()
+            .and_then(|queen_positions| BitboardSearch::new(dims, &queen_positions))
+    }
+}
+
+#[cfg(test)]
+mod bitboard_search_tests {
+    use super::BitboardSearch;
+
+    #[test]
+    fn bitboard_search_counts_8_queens_solutions() {
+        let mut search = BitboardSearch::new((8, 8), &[]).unwrap();
+        assert_eq!(search.count_remaining(), 92);
+    }
+
+    #[test]
+    fn mirrored_count_matches_full_count() {
+        [(1, 1), (4, 4), (8, 8), (9, 9), (5, 3), (3, 5), (7, 4), (64, 1)]
+            .iter()
+            .for_each(|&dims| {
+                let expected = BitboardSearch::new(dims, &[]).unwrap().count_remaining();
+                let mut search = BitboardSearch::new(dims, &[]).unwrap();
+                assert_eq!(search.count_mirrored(), Some(expected), "dims = {:?}", dims);
+            });
+    }
+
+    #[test]
+    fn mirrored_count_requires_a_fresh_search() {
+        let mut search = BitboardSearch::new((8, 8), &[(0, 0)]).unwrap();
+        assert_eq!(search.count_mirrored(), None);
+        let mut search = BitboardSearch::new((8, 8), &[]).unwrap();
+        search.step();
+        assert_eq!(search.count_mirrored(), None);
+        let mut search = BitboardSearch::new((5, 1), &[]).unwrap();
+        assert!(search.step());
+        assert_eq!(search.count_mirrored(), None);
+        assert_eq!(search.count_remaining(), 4);
+    }
+
+    #[test]
+    fn bitboard_search_rejects_empty_boards() {
+        assert!(BitboardSearch::new((0, 0), &[]).is_none());
+        assert!(BitboardSearch::new((5, 0), &[]).is_none());
+        assert!(BitboardSearch::new((0, 5), &[]).is_none());
+    }
+
+    #[test]
+    fn bitboard_search_rejects_wide_boards() {
+        assert!(BitboardSearch::new((64, 64), &[]).is_some());
+        assert!(BitboardSearch::new((65, 65), &[]).is_none());
+    }
+
+    #[test]
+    fn bitboard_search_transposes_tall_boards() {
+        assert!(BitboardSearch::new((8, 64), &[]).is_some());
+        assert!(BitboardSearch::new((8, 65), &[]).is_none());
+        let mut search = BitboardSearch::new((2, 4), &[(0, 0)]).unwrap();
+        while !search.step() {}
+        assert_eq!(search.get_solution(), vec![(0, 0), (1, 2)]);
+        assert_eq!(search.count_remaining(), 1);
+    }
+
+    #[test]
+    fn bitboard_search_rejects_two_fixed_queens_in_a_row() {
+        let mut search = BitboardSearch::new((8, 8), &[(0, 0), (4, 0)]).unwrap();
+        assert!(search.is_done());
+        assert_eq!(search.count_remaining(), 0);
+    }
+
+    #[test]
+    fn bitboard_search_completes_fixed_queens() {
+        let fixed = [(2, 0), (4, 1), (1, 2), (7, 3), (0, 4), (6, 5), (3, 6)];
+        let mut search = BitboardSearch::new((8, 8), &fixed).unwrap();
+        let expected = vec![(0, 4), (1, 2), (2, 0), (3, 6), (4, 1), (5, 7), (6, 5), (7, 3)];
+        while !search.step() {}
+        assert_eq!(search.get_solution(), expected);
+        assert_eq!(search.count_remaining(), 0);
+    }
+}
+
+#[cfg(test)]
+mod bitboard_solver_tests {
+    use super::super::{SearchMode, Solver};
+    use Board;
+
+    fn create_solver(n: u32, mode: SearchMode) -> Solver {
+        let mut solver = Solver::from(Board::from((n, n)));
+        solver.set_search_mode(mode);
+        solver
+    }
+
+    #[test]
+    fn bitboard_solutions_match_exhaustive_solutions() {
+        (1..10).for_each(|n| {
+            let bitboard = create_solver(n, SearchMode::Bitboard).solve();
+            let exhaustive = create_solver(n, SearchMode::Exhaustive).solve();
+            assert_eq!(bitboard, exhaustive, "n = {}", n);
+        });
+    }
+
+    #[test]
+    fn bitboard_solutions_match_exhaustive_for_non_square_boards() {
+        [(5, 3), (3, 5), (6, 4), (4, 6), (8, 1)].iter().for_each(|&dims| {
+            let mut bitboard = Solver::from(Board::from(dims));
+            bitboard.set_search_mode(SearchMode::Bitboard);
+            let mut exhaustive = Solver::from(Board::from(dims));
+            exhaustive.set_search_mode(SearchMode::Exhaustive);
+            assert_eq!(bitboard.solve(), exhaustive.solve(), "dims = {:?}", dims);
+        });
+    }
+
+    #[test]
+    fn bitboard_solver_counts_12_queens_solutions() {
+        assert_eq!(create_solver(12, SearchMode::Bitboard).count(), 14200);
+    }
+
+    #[test]
+    fn bitboard_solver_count_includes_ticked_solutions() {
+        let mut solver = create_solver(8, SearchMode::Bitboard);
+        while !solver.solution_exists() {
+            solver.tick();
+        }
+        assert_eq!(solver.count(), 92);
+    }
+
+    #[test]
+    fn wide_boards_fall_back_to_exhaustive_search() {
+        let mut solver = create_solver(65, SearchMode::Bitboard);
+        (0..100).for_each(|_| solver.tick());
+        assert!(!solver.is_done());
+    }
+
+    /// Count the solutions for n = 14..16, from OEIS A000170. This is slow
+    /// without optimizations, so run it using `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn bitboard_solver_counts_large_boards() {
+        let expected: &[(u32, u64)] = &[(14, 365_596), (15, 2_279_184), (16, 14_772_512)];
+        expected.iter().for_each(|&(n, count)| {
+            assert_eq!(create_solver(n, SearchMode::Bitboard).count(), count);
+        });
+    }
+}
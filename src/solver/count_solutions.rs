@@ -0,0 +1,80 @@
+use super::bitboard_search::BitboardSearch;
+use super::{SearchMode, Solver};
+use {Board, PosCoords};
+
+/// Count the solutions for an empty board with the given dimensions, without
+/// storing any of them. Half of the search is skipped using the reflection of
+/// the board across its vertical axis. Boards that are too large for the
+/// bitboard search fall back to `Solver::count` in exhaustive mode, which does
+/// store the solutions that it finds. A board with no rows or no columns has
+/// a single solution, which holds no queens.
+pub fn count_solutions(dims: PosCoords) -> u64 {
+    let count = BitboardSearch::new(dims, &[]).and_then(|mut search| search.count_mirrored());
+    match count {
+        Some(count) => count,
+        None => {
+            let mut solver = Solver::from(Board::from(dims));
+            solver.set_search_mode(SearchMode::Exhaustive);
+            solver.count()
+        }
+    }
+}
+
+#[cfg(test)]
+mod count_solutions_tests {
+    use super::count_solutions;
+    use solver::{SearchMode, Solver};
+    use Board;
+
+    /// The number of solutions to the n-queens problem for n = 1..12, taken
+    /// from OEIS A000170.
+    static SOLUTION_COUNTS: &[u64] = &[1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680, 14200];
+
+    #[test]
+    fn count_solutions_matches_known_counts() {
+        SOLUTION_COUNTS.iter().enumerate().for_each(|(i, &count)| {
+            let n = i as u32 + 1;
+            assert_eq!(count_solutions((n, n)), count, "n = {}", n);
+        });
+    }
+
+    #[test]
+    fn count_solutions_matches_solver_for_non_square_boards() {
+        [(5, 3), (3, 5), (6, 4), (7, 2), (8, 1)]
+            .iter()
+            .for_each(|&dims| {
+                let mut solver = Solver::from(Board::from(dims));
+                solver.set_search_mode(SearchMode::Exhaustive);
+                assert_eq!(count_solutions(dims), solver.count(), "dims = {:?}", dims);
+            });
+    }
+
+    #[test]
+    fn empty_boards_have_one_solution() {
+        for &dims in [(0, 0), (5, 0), (0, 5)].iter() {
+            assert_eq!(count_solutions(dims), 1, "dims = {:?}", dims);
+            for &mode in [SearchMode::Exhaustive, SearchMode::Bitboard].iter() {
+                let mut solver = Solver::from(Board::from(dims));
+                solver.set_search_mode(mode);
+                assert_eq!(solver.count(), 1, "dims = {:?}", dims);
+            }
+        }
+    }
+
+    /// Count the solutions for n = 13..17, from OEIS A000170. This is slow
+    /// without optimizations, so run it using `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn count_solutions_for_large_boards() {
+        let expected: &[(u32, u64)] = &[
+            (13, 73_712),
+            (14, 365_596),
+            (15, 2_279_184),
+            (16, 14_772_512),
+            (17, 95_815_104),
+        ];
+        expected.iter().for_each(|&(n, count)| {
+            assert_eq!(count_solutions((n, n)), count, "n = {}", n);
+        });
+    }
+}
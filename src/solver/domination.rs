@@ -0,0 +1,422 @@
+use std::collections::BTreeSet;
+
+use checker::check_board;
+use position::CoordIter;
+use {Board, CoordList, Piece, PosCoords, Topology};
+
+/// The kinds of dominating set that can be searched for. A set of queens
+/// dominates a board if every square is either occupied or attacked by one
+/// of the queens. Blocked squares do not need to be dominated.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Domination {
+    /// Every square is occupied or attacked by a queen.
+    #[default]
+    Standard,
+    /// Every square is occupied or attacked by a queen, and no two queens
+    /// attack each other.
+    Independent,
+    /// Every square is attacked by a queen, including the squares holding
+    /// queens, which must be attacked by another queen.
+    Total,
+}
+
+impl Domination {
+    /// Returns true if the pieces on the board form a dominating set of this
+    /// kind. The number of pieces is not checked, so this does not show that
+    /// the set is minimum.
+    pub fn is_dominated(self, board: &Board) -> bool {
+        let dims = board.dims();
+        let blocked = board.get_blocked_positions();
+        let queens = board.get_queen_positions();
+        let covered = queens
+            .iter()
+            .flat_map(|&queen| {
+                board
+                    .piece()
+                    .get_moves(queen, dims, &blocked, board.topology())
+                    .into_iter()
+                    .filter(move |&pos| self != Domination::Total || pos != queen)
+            })
+            .collect::<BTreeSet<PosCoords>>();
+        let is_covered = CoordIter::from(dims)
+            .filter(|pos| blocked.binary_search(pos).is_err())
+            .all(|pos| covered.contains(&pos));
+        is_covered && (self != Domination::Independent || !check_board(board).has_conflict)
+    }
+}
+
+/// Finds the smallest sets of queens that dominate a board. Sets of each
+/// size are searched in turn, starting from the queens already on the board,
+/// so the first sets found are minimum. At each step, the first square that
+/// is not yet dominated is found, and a queen is placed on each square that
+/// would dominate it. A branch is abandoned once the queens left to place
+/// cannot dominate the remaining squares, even if each dominated as many
+/// squares as any queen can.
+#[derive(Clone, Debug)]
+pub struct DominationSolver {
+    dims: PosCoords,
+    piece: Piece,
+    topology: Topology,
+    blocked: CoordList,
+    start: CoordList,
+    domination: Domination,
+}
+
+/// The squares dominated by a queen on each square of the board, stored as
+/// bitsets indexed by `y * width + x`.
+struct Coverage {
+    squares: Vec<PosCoords>,
+    covers: Vec<Vec<u64>>,
+    /// The squares that can hold a queen and dominate each square.
+    coverers: Vec<Vec<usize>>,
+    targets: Vec<u64>,
+    max_cover: u32,
+}
+
+impl DominationSolver {
+    /// Create a new solver for an empty board of queens with the given
+    /// dimensions, searching for standard dominating sets.
+    pub fn new(dims: PosCoords) -> DominationSolver {
+        DominationSolver::from(Board::from(dims))
+    }
+
+    /// Return the kind of dominating set searched for.
+    pub fn domination(&self) -> Domination {
+        self.domination
+    }
+
+    /// Set the kind of dominating set searched for.
+    pub fn set_domination(&mut self, domination: Domination) {
+        self.domination = domination;
+    }
+
+    /// Find a minimum dominating set, containing the queens already on the
+    /// board. Returns `None` if there is no dominating set, as for total
+    /// domination of a 1x1 board.
+    pub fn solve(&self) -> Option<Board> {
+        self.search(false).into_iter().next()
+    }
+
+    /// Find every minimum dominating set containing the queens already on
+    /// the board, sorted by their queen positions. Returns an empty list if
+    /// there is no dominating set.
+    pub fn solve_all(&self) -> Vec<Board> {
+        self.search(true)
+    }
+
+    /// Search for dominating sets of each size in turn, and return the sets
+    /// of the first size for which any are found.
+    fn search(&self, find_all: bool) -> Vec<Board> {
+        let coverage = self.get_coverage();
+        let mut chosen = self
+            .start
+            .iter()
+            .map(|pos| self.get_index(*pos))
+            .collect::<Vec<usize>>();
+        if self.domination == Domination::Independent && self.start_has_conflict() {
+            return Vec::new();
+        }
+        let mut covered = vec![0; coverage.targets.len()];
+        for &i in chosen.iter() {
+            or_assign(&mut covered, &coverage.covers[i]);
+        }
+        for size in chosen.len()..=coverage.squares.len() {
+            let mut found = BTreeSet::new();
+            self.search_from(&coverage, &mut chosen, &covered, size, find_all, &mut found);
+            if !found.is_empty() {
+                return found
+                    .into_iter()
+                    .map(|queens| self.create_board(queens))
+                    .collect();
+            }
+        }
+        Vec::new()
+    }
+
+    /// Recursively place queens until every target square is dominated, or
+    /// the given number of queens have been placed. Each dominating set is
+    /// added to `found`. Returns true once the search should stop.
+    fn search_from(
+        &self,
+        coverage: &Coverage,
+        chosen: &mut Vec<usize>,
+        covered: &[u64],
+        size: usize,
+        find_all: bool,
+        found: &mut BTreeSet<CoordList>,
+    ) -> bool {
+        let uncovered = coverage
+            .targets
+            .iter()
+            .zip(covered.iter())
+            .map(|(&target, &covered)| target & !covered)
+            .collect::<Vec<u64>>();
+        let first = uncovered
+            .iter()
+            .position(|&word| word != 0)
+            .map(|word| word * 64 + uncovered[word].trailing_zeros() as usize);
+        let first = match first {
+            Some(first) => first,
+            None => {
+                let mut queens = chosen
+                    .iter()
+                    .map(|&i| coverage.squares[i])
+                    .collect::<CoordList>();
+                queens.sort();
+                found.insert(queens);
+                return !find_all;
+            }
+        };
+        let num_uncovered = uncovered.iter().map(|word| word.count_ones()).sum::<u32>();
+        let num_left = (size - chosen.len()) as u32;
+        if num_uncovered > num_left * coverage.max_cover {
+            return false;
+        }
+        for &i in coverage.coverers[first].iter() {
+            if chosen.contains(&i) || !self.can_place(coverage, chosen, i) {
+                continue;
+            }
+            let mut next = covered.to_vec();
+            or_assign(&mut next, &coverage.covers[i]);
+            chosen.push(i);
+            let stop = self.search_from(coverage, chosen, &next, size, find_all, found);
+            chosen.pop();
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns true if a queen can be placed on the square with the given
+    /// index. For independent domination, the queen cannot attack any of
+    /// the queens already placed.
+    fn can_place(&self, coverage: &Coverage, chosen: &[usize], i: usize) -> bool {
+        if self.domination != Domination::Independent {
+            return true;
+        }
+        let pos = coverage.squares[i];
+        !chosen
+            .iter()
+            .any(|&j| self.attacks(pos, coverage.squares[j]))
+    }
+
+    /// Returns true if any two of the queens already on the board attack each
+    /// other.
+    fn start_has_conflict(&self) -> bool {
+        self.start
+            .iter()
+            .enumerate()
+            .any(|(i, &a)| self.start[i + 1..].iter().any(|&b| self.attacks(a, b)))
+    }
+
+    /// Returns true if pieces at positions `a` and `b` attack each other.
+    fn attacks(&self, a: PosCoords, b: PosCoords) -> bool {
+        self.piece
+            .attacks(a, b, self.dims, &self.blocked, self.topology)
+    }
+
+    /// Find the squares dominated by a queen on each square of the board.
+    fn get_coverage(&self) -> Coverage {
+        let (width, height) = self.dims;
+        let squares = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .collect::<Vec<PosCoords>>();
+        let num_words = squares.len().div_ceil(64);
+        let mut targets = vec![0u64; num_words];
+        let mut covers = vec![vec![0u64; num_words]; squares.len()];
+        let mut coverers = vec![Vec::new(); squares.len()];
+        for (i, &pos) in squares.iter().enumerate() {
+            if self.blocked.binary_search(&pos).is_ok() {
+                continue;
+            }
+            targets[i / 64] |= 1 << (i % 64);
+            let moves = self
+                .piece
+                .get_moves(pos, self.dims, &self.blocked, self.topology);
+            for covered in moves {
+                if self.domination == Domination::Total && covered == pos {
+                    continue;
+                }
+                let j = self.get_index(covered);
+                covers[i][j / 64] |= 1 << (j % 64);
+                coverers[j].push(i);
+            }
+        }
+        let max_cover = covers
+            .iter()
+            .map(|cover| cover.iter().map(|word| word.count_ones()).sum())
+            .max()
+            .unwrap_or(0);
+        Coverage {
+            squares,
+            covers,
+            coverers,
+            targets,
+            max_cover,
+        }
+    }
+
+    /// Return the index of a square in the coverage bitsets.
+    fn get_index(&self, (x, y): PosCoords) -> usize {
+        (y * self.dims.0 + x) as usize
+    }
+
+    /// Create a board holding the given queens, along with the blocked
+    /// squares, the kind of piece, and the topology of the board.
+    fn create_board(&self, queens: CoordList) -> Board {
+        let mut board = Board::with_queens(self.dims, queens);
+        board.set_piece(self.piece);
+        board.set_topology(self.topology);
+        for &pos in self.blocked.iter() {
+            board.add_blocked(pos);
+        }
+        board
+    }
+}
+
+impl From<Board> for DominationSolver {
+    fn from(board: Board) -> DominationSolver {
+        DominationSolver {
+            dims: board.dims(),
+            piece: board.piece(),
+            topology: board.topology(),
+            blocked: board.get_blocked_positions(),
+            start: board.get_queen_positions(),
+            domination: Domination::default(),
+        }
+    }
+}
+
+/// Add the bits set in `other` to `bits`.
+fn or_assign(bits: &mut [u64], other: &[u64]) {
+    for (word, &other) in bits.iter_mut().zip(other.iter()) {
+        *word |= other;
+    }
+}
+
+#[cfg(test)]
+mod domination_tests {
+    use super::{Domination, DominationSolver};
+    use {Board, Piece};
+
+    fn solve(n: u32, domination: Domination) -> Option<Board> {
+        let mut solver = DominationSolver::new((n, n));
+        solver.set_domination(domination);
+        solver.solve()
+    }
+
+    /// Find the minimum dominating sets by checking every set of squares on
+    /// a small board, in increasing order of size.
+    fn brute_force(n: u32, domination: Domination) -> Vec<Board> {
+        let squares = (0..n * n).map(|i| (i % n, i / n)).collect::<Vec<_>>();
+        for size in 1..=squares.len() as u32 {
+            let mut found = (0u32..1 << squares.len())
+                .filter(|mask| mask.count_ones() == size)
+                .map(|mask| {
+                    let queens = (0..squares.len())
+                        .filter(|i| mask & (1 << i) != 0)
+                        .map(|i| squares[i]);
+                    Board::with_queens((n, n), queens)
+                })
+                .filter(|b| domination.is_dominated(b))
+                .collect::<Vec<_>>();
+            if !found.is_empty() {
+                found.sort_by_key(|board| board.get_queen_positions());
+                return found;
+            }
+        }
+        Vec::new()
+    }
+
+    /// The domination numbers of the queen graph for n = 1..8, taken from
+    /// OEIS A075458.
+    #[test]
+    fn domination_numbers_are_known() {
+        let expected = [1, 1, 1, 2, 3, 3, 4, 5];
+        for (i, &size) in expected.iter().enumerate() {
+            let n = i as u32 + 1;
+            let board = solve(n, Domination::Standard).unwrap();
+            assert_eq!(board.get_queen_positions().len(), size, "n = {}", n);
+            assert!(Domination::Standard.is_dominated(&board));
+        }
+    }
+
+    /// The independent domination numbers of the queen graph for n = 1..8,
+    /// taken from OEIS A075324.
+    #[test]
+    fn independent_domination_numbers_are_known() {
+        let expected = [1, 1, 1, 3, 3, 4, 4, 5];
+        for (i, &size) in expected.iter().enumerate() {
+            let n = i as u32 + 1;
+            let board = solve(n, Domination::Independent).unwrap();
+            assert_eq!(board.get_queen_positions().len(), size, "n = {}", n);
+            assert!(Domination::Independent.is_dominated(&board));
+        }
+    }
+
+    #[test]
+    fn total_domination_needs_attacked_queens() {
+        assert!(solve(1, Domination::Total).is_none());
+        let board = solve(4, Domination::Total).unwrap();
+        assert!(Domination::Total.is_dominated(&board));
+        assert!(!Domination::Total.is_dominated(&Board::with_queens((2, 2), vec![(0, 0)])));
+        assert!(Domination::Standard.is_dominated(&Board::with_queens((2, 2), vec![(0, 0)])));
+    }
+
+    #[test]
+    fn every_minimum_set_is_found() {
+        for &domination in [
+            Domination::Standard,
+            Domination::Independent,
+            Domination::Total,
+        ]
+        .iter()
+        {
+            for n in 2..5 {
+                let mut solver = DominationSolver::new((n, n));
+                solver.set_domination(domination);
+                let expected = brute_force(n, domination);
+                assert_eq!(solver.solve_all(), expected, "{:?} n = {}", domination, n);
+            }
+        }
+    }
+
+    #[test]
+    fn queens_on_the_board_are_kept() {
+        let b = Board::with_queens((4, 4), vec![(0, 0)]);
+        let solutions = DominationSolver::from(b).solve_all();
+        assert!(!solutions.is_empty());
+        for board in solutions {
+            assert_eq!(board.get_queen_positions().len(), 2);
+            assert!(board.get_queen_positions().contains(&(0, 0)));
+        }
+        let b = Board::with_queens((4, 4), vec![(0, 0), (1, 1)]);
+        let mut solver = DominationSolver::from(b);
+        solver.set_domination(Domination::Independent);
+        assert!(solver.solve().is_none());
+    }
+
+    /// Kings dominate the board in 3x3 blocks, so nine kings are needed on
+    /// a 9x9 board. Three rooks dominate a 3x3 board if they share no row or
+    /// share no column, which 27 + 27 - 6 = 48 sets do.
+    #[test]
+    fn other_pieces_are_dominated() {
+        let mut b = Board::from((9, 9));
+        b.set_piece(Piece::King);
+        let board = DominationSolver::from(b).solve().unwrap();
+        assert_eq!(board.piece(), Piece::King);
+        assert_eq!(board.get_queen_positions().len(), 9);
+        let mut b = Board::from((3, 3));
+        b.set_piece(Piece::Rook);
+        assert_eq!(DominationSolver::from(b).solve_all().len(), 48);
+    }
+
+    #[test]
+    fn blocked_squares_do_not_need_to_be_dominated() {
+        let b = Board::with_blocked((3, 1), vec![(1, 0)]);
+        let board = DominationSolver::from(b).solve().unwrap();
+        assert_eq!(board.get_queen_positions().len(), 2);
+        assert!(board.is_blocked(&(1, 0)));
+    }
+}
@@ -0,0 +1,187 @@
+use super::Solver;
+use {CoordList, PosCoords};
+
+impl Solver {
+    /// Tick the solver forward one iteration using line-by-line backtracking.
+    /// Pop a state off of the state heap, and find its first empty line. If
+    /// every line is occupied, the state is a solution. Otherwise, push a new
+    /// state for each safe square in that line.
+    pub(super) fn tick_exhaustive(&mut self) {
+        if let Some(queen_positions) = self.state_heap.pop() {
+            match self.get_first_empty_line(&queen_positions) {
+                Some(line) => {
+                    let next_moves = self.get_line_moves(&queen_positions, line);
+                    self.state_heap.extend(next_moves);
+                }
+                None => {
+                    if !self.has_conflict(&queen_positions) {
+                        self.add_solution(queen_positions);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Find the first line that does not contain a queen, if one exists.
+    /// Lines are the rows of the board, unless the board is taller than it
+    /// is wide, in which case they are its columns. This way, a solution
+    /// holds exactly one queen in each line.
+    pub(super) fn get_first_empty_line(&self, queen_positions: &[PosCoords]) -> Option<u32> {
+        let num_lines = self.dimensions.0.min(self.dimensions.1);
+        (0..num_lines).find(|&line| {
+            queen_positions
+                .iter()
+                .all(|&pos| self.get_line_index(pos) != line)
+        })
+    }
+
+    /// Get the states formed by placing a queen on each square of the given
+    /// line that is not attacked by the existing pieces. States are returned
+    /// in descending order, so that they are popped in ascending order.
+    pub(super) fn get_line_moves(&self, queen_positions: &[PosCoords], line: u32) -> Vec<CoordList> {
+        let is_tall = self.is_tall();
+        let line_length = self.dimensions.0.max(self.dimensions.1);
+        (0..line_length)
+            .rev()
+            .map(|i| if is_tall { (line, i) } else { (i, line) })
+            .filter(|&pos| {
+                !queen_positions
+                    .iter()
+                    .any(|&queen| self.attacks(queen, pos))
+            })
+            .map(|pos| {
+                let mut next = queen_positions.to_vec();
+                next.push(pos);
+                next.sort();
+                next
+            })
+            .collect()
+    }
+
+    /// Returns true if any pair of pieces in the list attack each other. Moves
+    /// generated by the search are always safe, so this is only needed to
+    /// reject a conflicting starting state.
+    fn has_conflict(&self, queen_positions: &[PosCoords]) -> bool {
+        queen_positions.iter().enumerate().any(|(i, &a)| {
+            queen_positions[i + 1..]
+                .iter()
+                .any(|&b| self.attacks(a, b))
+        })
+    }
+
+    /// Returns true if the board is taller than it is wide.
+    fn is_tall(&self) -> bool {
+        self.dimensions.1 > self.dimensions.0
+    }
+
+    /// Return the index of the line containing a position.
+    fn get_line_index(&self, pos: PosCoords) -> u32 {
+        if self.is_tall() {
+            pos.0
+        } else {
+            pos.1
+        }
+    }
+}
+
+#[cfg(test)]
+mod exhaustive_search_tests {
+    use super::super::{SearchMode, Solver};
+    use topology::gcd;
+    use {Board, Topology};
+
+    /// The number of solutions to the n-queens problem for n = 1..12, taken
+    /// from OEIS A000170.
+    static SOLUTION_COUNTS: &[usize] = &[1, 0, 0, 2, 10, 4, 40, 92, 352, 724, 2680, 14200];
+
+    fn solve_exhaustive(n: u32) -> usize {
+        let mut solver = Solver::from(Board::from((n, n)));
+        solver.set_search_mode(SearchMode::Exhaustive);
+        solver.solve().len()
+    }
+
+    #[test]
+    fn exhaustive_search_finds_all_92_solutions() {
+        assert_eq!(solve_exhaustive(8), 92);
+    }
+
+    #[test]
+    fn exhaustive_search_matches_known_solution_counts() {
+        SOLUTION_COUNTS
+            .iter()
+            .enumerate()
+            .for_each(|(i, &expected)| {
+                let n = i as u32 + 1;
+                assert_eq!(solve_exhaustive(n), expected, "n = {}", n);
+            });
+    }
+
+    #[test]
+    fn exhaustive_search_completes_partial_board() {
+        let b: Board = [(2, 0), (4, 1), (1, 2), (7, 3), (0, 4), (6, 5), (3, 6)]
+            .iter()
+            .cloned()
+            .collect();
+        let mut solver = Solver::from(b);
+        solver.set_search_mode(SearchMode::Exhaustive);
+        let expected = vec![(0, 4), (1, 2), (2, 0), (3, 6), (4, 1), (5, 7), (6, 5), (7, 3)];
+        let soln_set = solver.solve();
+        assert_eq!(soln_set.len(), 1);
+        assert!(soln_set.contains(&expected));
+    }
+
+    #[test]
+    fn exhaustive_search_solves_non_square_boards() {
+        let solve = |dims| {
+            let mut solver = Solver::from(Board::from(dims));
+            solver.set_search_mode(SearchMode::Exhaustive);
+            solver.solve()
+        };
+        assert_eq!(solve((4, 2)).len(), 6);
+        assert_eq!(solve((2, 4)).len(), 6);
+        assert_eq!(solve((5, 3)).len(), solve((3, 5)).len());
+        solve((3, 5)).iter().for_each(|soln| assert_eq!(soln.len(), 3));
+    }
+
+    /// Toroidal solutions exist exactly when gcd(n, 6) = 1. The counts for
+    /// n = 5, 7, and 11 are taken from OEIS A007705.
+    #[test]
+    fn toroidal_solutions_exist_when_n_is_coprime_to_6() {
+        let solve_toroidal = |n| {
+            let mut b = Board::from((n, n));
+            b.set_topology(Topology::Toroidal);
+            let mut solver = Solver::from(b);
+            solver.set_search_mode(SearchMode::Exhaustive);
+            solver.solve().len()
+        };
+        for n in 1..12 {
+            assert_eq!(solve_toroidal(n) > 0, gcd(n, 6) == 1, "n = {}", n);
+        }
+        assert_eq!(solve_toroidal(5), 10);
+        assert_eq!(solve_toroidal(7), 28);
+        assert_eq!(solve_toroidal(11), 88);
+    }
+
+    #[test]
+    fn toroidal_solutions_match_in_every_search_mode() {
+        let mut b = Board::from((7, 7));
+        b.set_topology(Topology::Toroidal);
+        let mut expected = Solver::from(b.clone());
+        expected.set_search_mode(SearchMode::Exhaustive);
+        let expected = expected.solve();
+        for &mode in [SearchMode::Heuristic, SearchMode::Bitboard].iter() {
+            let mut solver = Solver::from(b.clone());
+            solver.set_search_mode(mode);
+            assert_eq!(solver.solve(), expected, "{:?}", mode);
+        }
+        assert_eq!(Solver::from(b).solve_parallel(3), expected);
+    }
+
+    #[test]
+    fn exhaustive_search_rejects_conflicting_start() {
+        let b: Board = [(0, 0), (1, 1)].iter().cloned().collect();
+        let mut solver = Solver::from(b);
+        solver.set_search_mode(SearchMode::Exhaustive);
+        assert!(solver.solve().is_empty());
+    }
+}
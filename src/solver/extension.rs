@@ -0,0 +1,141 @@
+use super::{SearchMode, Solver};
+use checker::check_board;
+use Board;
+
+/// The result of trying to extend a partial placement of queens to a full
+/// solution, returned by `extend` and `extend_all`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Extension {
+    /// No solution contains the queens already on the board.
+    Impossible,
+    /// A single solution containing the queens already on the board.
+    Completion(Board),
+    /// Every solution containing the queens already on the board, sorted by
+    /// their queen positions. This is never empty.
+    Completions(Vec<Board>),
+}
+
+impl Extension {
+    /// Returns true if the board can be extended to at least one solution.
+    pub fn is_possible(&self) -> bool {
+        *self != Extension::Impossible
+    }
+}
+
+/// Find a single solution that contains every queen on the board, or prove
+/// that none exists. The queens on the board are kept fixed, and the search
+/// is exhaustive, so `Extension::Impossible` is a definitive answer.
+pub fn extend(board: &Board) -> Extension {
+    if check_board(board).has_conflict {
+        return Extension::Impossible;
+    }
+    match create_solver(board).get_next_solution() {
+        Some(solution) => Extension::Completion(solution),
+        None => Extension::Impossible,
+    }
+}
+
+/// Find every solution that contains every queen on the board. Returns
+/// `Extension::Impossible` if there are none.
+pub fn extend_all(board: &Board) -> Extension {
+    if check_board(board).has_conflict {
+        return Extension::Impossible;
+    }
+    let completions = create_solver(board).solve_sorted().boards();
+    match completions.is_empty() {
+        true => Extension::Impossible,
+        false => Extension::Completions(completions),
+    }
+}
+
+/// Create an exhaustive solver starting from the queens on the board.
+fn create_solver(board: &Board) -> Solver {
+    let mut solver = Solver::from(board.clone());
+    solver.set_search_mode(SearchMode::Bitboard);
+    solver
+}
+
+#[cfg(test)]
+mod extension_tests {
+    use super::{extend, extend_all, Extension};
+    use Board;
+
+    ///   01234567
+    ///   --------
+    /// 7|        |
+    /// 6|   Q    |
+    /// 5|      Q |
+    /// 4|Q       |
+    /// 3|       Q|
+    /// 2| Q      |
+    /// 1|    Q   |
+    /// 0|  Q     |
+    ///   --------
+    #[test]
+    fn seven_queens_are_extended_to_a_solution() {
+        let queens = vec![(2, 0), (4, 1), (1, 2), (7, 3), (0, 4), (6, 5), (3, 6)];
+        let b = Board::with_queens((8, 8), queens.clone());
+        let mut expected = queens;
+        expected.push((5, 7));
+        let expected = Board::with_queens((8, 8), expected);
+        assert_eq!(extend(&b), Extension::Completion(expected.clone()));
+        assert_eq!(extend_all(&b), Extension::Completions(vec![expected]));
+    }
+
+    /// Neither solution to the 4-queens problem has a queen in a corner.
+    #[test]
+    fn impossible_placements_are_detected() {
+        let b = Board::with_queens((4, 4), vec![(0, 0)]);
+        assert_eq!(extend(&b), Extension::Impossible);
+        assert_eq!(extend_all(&b), Extension::Impossible);
+        assert!(!extend(&b).is_possible());
+        let b = Board::with_queens((3, 3), vec![]);
+        assert_eq!(extend(&b), Extension::Impossible);
+    }
+
+    #[test]
+    fn conflicting_placements_are_impossible() {
+        let b = Board::with_queens((8, 8), vec![(0, 0), (7, 7)]);
+        assert_eq!(extend(&b), Extension::Impossible);
+        let b = Board::with_queens((8, 8), vec![(0, 3), (5, 3)]);
+        assert_eq!(extend_all(&b), Extension::Impossible);
+    }
+
+    #[test]
+    fn empty_board_is_extended_to_every_solution() {
+        match extend_all(&Board::from((6, 6))) {
+            Extension::Completions(boards) => {
+                assert_eq!(boards.len(), 4);
+                let queens = boards
+                    .iter()
+                    .map(|board| board.get_queen_positions())
+                    .collect::<Vec<_>>();
+                assert!(queens.windows(2).all(|pair| pair[0] < pair[1]));
+            }
+            res => panic!("expected completions, found {:?}", res),
+        }
+    }
+
+    #[test]
+    fn solutions_are_their_own_completion() {
+        let b = Board::constructive_solution(10).unwrap();
+        assert_eq!(extend(&b), Extension::Completion(b));
+    }
+
+    #[test]
+    fn tall_and_large_boards_are_extended() {
+        let b = Board::with_queens((3, 5), vec![(1, 4)]);
+        match extend(&b) {
+            Extension::Completion(solution) => {
+                assert!(solution.get_queen_positions().contains(&(1, 4)));
+                assert_eq!(solution.get_queen_positions().len(), 3);
+            }
+            res => panic!("expected a completion, found {:?}", res),
+        }
+        let solution = Board::constructive_solution(70).unwrap();
+        let mut queens = solution.get_queen_positions();
+        queens.retain(|&(x, _)| x != 35);
+        let b = Board::with_queens((70, 70), queens);
+        assert_eq!(extend(&b), Extension::Completion(solution));
+    }
+}
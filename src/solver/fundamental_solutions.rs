@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use super::Solver;
+use Board;
+
+/// A fundamental solution, representing a class of solutions that can be
+/// mapped to one another using the symmetries of the board.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FundamentalSolution {
+    /// The canonical form of the solutions in this class.
+    pub board: Board,
+    /// The number of distinct solutions in this class. For a square board,
+    /// this is either 1, 2, 4, or 8.
+    pub orbit_size: usize,
+}
+
+impl Solver {
+    /// Find the solutions using the current search mode, and return one
+    /// fundamental solution for each class of symmetric solutions, sorted by
+    /// their queen positions. Use an exhaustive search mode to find every
+    /// fundamental solution.
+    pub fn solve_fundamental(&mut self) -> Vec<FundamentalSolution> {
+        let mut classes: HashMap<Board, usize> = HashMap::new();
+        for queen_positions in self.solve() {
+            let canonical = self.create_board(queen_positions).canonical_form();
+            let orbit_size = canonical.orbit_size();
+            classes.insert(canonical, orbit_size);
+        }
+        let mut fundamental = classes
+            .into_iter()
+            .map(|(board, orbit_size)| FundamentalSolution { board, orbit_size })
+            .collect::<Vec<FundamentalSolution>>();
+        fundamental.sort_by(|a, b| {
+            a.board
+                .get_queen_positions()
+                .cmp(&b.board.get_queen_positions())
+        });
+        fundamental
+    }
+}
+
+#[cfg(test)]
+mod fundamental_solutions_tests {
+    use super::super::{SearchMode, Solver};
+    use Board;
+
+    /// The number of fundamental solutions to the n-queens problem for
+    /// n = 1..10, taken from OEIS A002562.
+    static FUNDAMENTAL_COUNTS: &[usize] = &[1, 0, 0, 1, 2, 1, 6, 12, 46, 92];
+
+    fn create_solver(n: u32) -> Solver {
+        let mut solver = Solver::from(Board::from((n, n)));
+        solver.set_search_mode(SearchMode::Bitboard);
+        solver
+    }
+
+    #[test]
+    fn fundamental_solution_counts_are_correct() {
+        FUNDAMENTAL_COUNTS
+            .iter()
+            .enumerate()
+            .for_each(|(i, &expected)| {
+                let n = i as u32 + 1;
+                let actual = create_solver(n).solve_fundamental().len();
+                assert_eq!(actual, expected, "n = {}", n);
+            });
+    }
+
+    #[test]
+    fn eight_queens_has_12_fundamental_solutions() {
+        let fundamental = create_solver(8).solve_fundamental();
+        assert_eq!(fundamental.len(), 12);
+        let num_symmetric = fundamental.iter().filter(|f| f.orbit_size == 4).count();
+        let num_asymmetric = fundamental.iter().filter(|f| f.orbit_size == 8).count();
+        assert_eq!((num_symmetric, num_asymmetric), (1, 11));
+    }
+
+    #[test]
+    fn orbit_sizes_sum_to_total_solution_count() {
+        (1..10).for_each(|n| {
+            let total = create_solver(n).count() as usize;
+            let orbit_sum: usize = create_solver(n)
+                .solve_fundamental()
+                .iter()
+                .map(|f| f.orbit_size)
+                .sum();
+            assert_eq!(orbit_sum, total, "n = {}", n);
+        });
+    }
+
+    #[test]
+    fn fundamental_solutions_are_canonical() {
+        create_solver(8)
+            .solve_fundamental()
+            .into_iter()
+            .for_each(|f| assert_eq!(f.board.canonical_form(), f.board));
+    }
+}
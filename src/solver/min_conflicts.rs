@@ -0,0 +1,244 @@
+use rand::{self, Rng};
+
+use random::seeded_rng;
+use Board;
+
+/// The number of random rows to try for each column while building the
+/// starting permutation, before accepting a row with diagonal conflicts.
+const MAX_START_TRIES: u32 = 128;
+
+/// The default maximum number of queens that are moved before giving up.
+const DEFAULT_MAX_STEPS: u64 = 100_000;
+
+/// The minimum number of queens that are moved before restarting from a new
+/// permutation. Larger boards are given one step per column.
+const MIN_RESTART_INTERVAL: u64 = 100;
+
+/// Min-conflicts local search for very large square boards, which cannot be
+/// solved using backtracking. The search starts from a random permutation,
+/// built so that most queens avoid sharing a diagonal, and then repeatedly
+/// moves the most conflicted queen to the row in its column with the fewest
+/// conflicts. The number of queens in each row and diagonal is updated
+/// incrementally as queens are moved, so that each step takes linear time.
+#[derive(Clone, Debug)]
+pub struct MinConflictsSolver {
+    n: u32,
+    max_steps: u64,
+    num_steps: u64,
+    rows: Vec<u32>,
+    row_counts: Vec<u32>,
+    diag_counts: Vec<u32>,
+    anti_diag_counts: Vec<u32>,
+}
+
+impl MinConflictsSolver {
+    /// Create a new solver for an n x n board.
+    pub fn new(n: u32) -> MinConflictsSolver {
+        MinConflictsSolver {
+            n,
+            max_steps: DEFAULT_MAX_STEPS,
+            num_steps: 0,
+            rows: Vec::new(),
+            row_counts: Vec::new(),
+            diag_counts: Vec::new(),
+            anti_diag_counts: Vec::new(),
+        }
+    }
+
+    /// Set the maximum number of queens that are moved before the search
+    /// gives up, across every restart.
+    pub fn set_max_steps(&mut self, max_steps: u64) {
+        self.max_steps = max_steps;
+    }
+
+    /// Return the number of queens that were moved by the last search.
+    pub fn num_steps(&self) -> u64 {
+        self.num_steps
+    }
+
+    /// Search for a solution, using the thread-local random number generator.
+    /// Returns `None` if no solution was found within the maximum number of
+    /// steps, which is always the case for n = 2 and n = 3.
+    pub fn solve(&mut self) -> Option<Board> {
+        self.solve_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Search for a solution, using a random number generator created from
+    /// the given seed. The same seed always produces the same solution.
+    pub fn solve_with_seed(&mut self, seed: u64) -> Option<Board> {
+        self.solve_with_rng(&mut seeded_rng(seed))
+    }
+
+    /// Search for a solution, using the given random number generator.
+    pub fn solve_with_rng<R: Rng>(&mut self, rng: &mut R) -> Option<Board> {
+        let restart_interval = MIN_RESTART_INTERVAL.max(self.n as u64);
+        self.num_steps = 0;
+        self.place_initial_queens(rng);
+        let mut restart_at = restart_interval;
+        loop {
+            let col = match self.get_most_conflicted(rng) {
+                Some(col) => col,
+                None => return Some(self.to_board()),
+            };
+            if self.num_steps >= self.max_steps {
+                return None;
+            }
+            if self.num_steps >= restart_at {
+                self.place_initial_queens(rng);
+                restart_at = self.num_steps + restart_interval;
+                continue;
+            }
+            self.move_queen(col, rng);
+            self.num_steps += 1;
+        }
+    }
+
+    /// Place one queen in each row and column, trying a few random rows for
+    /// each column to find one without diagonal conflicts.
+    fn place_initial_queens<R: Rng>(&mut self, rng: &mut R) {
+        let n = self.n as usize;
+        self.rows = vec![0; n];
+        self.row_counts = vec![0; n];
+        self.diag_counts = vec![0; (2 * n).saturating_sub(1)];
+        self.anti_diag_counts = vec![0; (2 * n).saturating_sub(1)];
+        let mut unused = (0..self.n).collect::<Vec<u32>>();
+        for x in 0..self.n {
+            let index = (0..MAX_START_TRIES)
+                .map(|_| rng.gen_range(0, unused.len()))
+                .find(|&i| self.count_conflicts(x, unused[i]) == 0);
+            let index = index.unwrap_or_else(|| rng.gen_range(0, unused.len()));
+            let y = unused.swap_remove(index);
+            self.place_queen(x, y);
+        }
+    }
+
+    /// Find the queen with the most conflicts, breaking ties randomly.
+    /// Returns `None` if no queen is in conflict.
+    fn get_most_conflicted<R: Rng>(&self, rng: &mut R) -> Option<u32> {
+        let mut most_conflicted = None;
+        let mut max_conflicts = 0;
+        let mut num_ties = 0;
+        for x in 0..self.n {
+            // Each count includes the queen itself.
+            let conflicts = self.count_conflicts(x, self.rows[x as usize]) - 3;
+            if conflicts == 0 || conflicts < max_conflicts {
+                continue;
+            }
+            if conflicts > max_conflicts {
+                max_conflicts = conflicts;
+                num_ties = 0;
+            }
+            num_ties += 1;
+            if rng.gen_range(0, num_ties) == 0 {
+                most_conflicted = Some(x);
+            }
+        }
+        most_conflicted
+    }
+
+    /// Move the queen in the given column to the row with the fewest
+    /// conflicts, other than its current row, breaking ties randomly.
+    fn move_queen<R: Rng>(&mut self, x: u32, rng: &mut R) {
+        let current = self.rows[x as usize];
+        self.remove_queen(x, current);
+        let mut best_row = current;
+        let mut min_conflicts = u32::MAX;
+        let mut num_ties = 0;
+        for y in (0..self.n).filter(|&y| y != current) {
+            let conflicts = self.count_conflicts(x, y);
+            if conflicts > min_conflicts {
+                continue;
+            }
+            if conflicts < min_conflicts {
+                min_conflicts = conflicts;
+                num_ties = 0;
+            }
+            num_ties += 1;
+            if rng.gen_range(0, num_ties) == 0 {
+                best_row = y;
+            }
+        }
+        self.place_queen(x, best_row);
+    }
+
+    /// Return the number of queens sharing a row or diagonal with a square.
+    fn count_conflicts(&self, x: u32, y: u32) -> u32 {
+        let (diag, anti_diag) = self.get_diags(x, y);
+        self.row_counts[y as usize] + self.diag_counts[diag] + self.anti_diag_counts[anti_diag]
+    }
+
+    fn place_queen(&mut self, x: u32, y: u32) {
+        let (diag, anti_diag) = self.get_diags(x, y);
+        self.rows[x as usize] = y;
+        self.row_counts[y as usize] += 1;
+        self.diag_counts[diag] += 1;
+        self.anti_diag_counts[anti_diag] += 1;
+    }
+
+    fn remove_queen(&mut self, x: u32, y: u32) {
+        let (diag, anti_diag) = self.get_diags(x, y);
+        self.row_counts[y as usize] -= 1;
+        self.diag_counts[diag] -= 1;
+        self.anti_diag_counts[anti_diag] -= 1;
+    }
+
+    /// Return the index of the diagonal and anti-diagonal of a square.
+    fn get_diags(&self, x: u32, y: u32) -> (usize, usize) {
+        ((x + self.n - 1 - y) as usize, (x + y) as usize)
+    }
+
+    fn to_board(&self) -> Board {
+        let queens = self.rows.iter().enumerate().map(|(x, &y)| (x as u32, y));
+        Board::with_queens((self.n, self.n), queens)
+    }
+}
+
+#[cfg(test)]
+mod min_conflicts_tests {
+    use super::MinConflictsSolver;
+    use checker::check_board;
+
+    #[test]
+    fn min_conflicts_solves_small_boards() {
+        for &n in [1, 4, 5, 8, 9, 20].iter() {
+            let board = MinConflictsSolver::new(n).solve_with_seed(n as u64).unwrap();
+            assert_eq!(board.dims(), (n, n));
+            assert!(check_board(&board).is_solved, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn same_seed_produces_same_solution() {
+        let a = MinConflictsSolver::new(50).solve_with_seed(9);
+        let b = MinConflictsSolver::new(50).solve_with_seed(9);
+        assert!(a.is_some());
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn min_conflicts_gives_up_when_no_solution_exists() {
+        for &n in [2, 3].iter() {
+            let mut solver = MinConflictsSolver::new(n);
+            solver.set_max_steps(1000);
+            assert_eq!(solver.solve_with_seed(0), None);
+            assert_eq!(solver.num_steps(), 1000);
+        }
+    }
+
+    #[test]
+    fn min_conflicts_solves_large_boards() {
+        let board = MinConflictsSolver::new(2000).solve_with_seed(0).unwrap();
+        assert!(check_board(&board).is_solved);
+    }
+
+    /// Solve the boards used as load test fixtures. This is slow without
+    /// optimizations, so run it using `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn min_conflicts_solves_very_large_boards() {
+        for &n in [10_000, 1_000_000].iter() {
+            let board = MinConflictsSolver::new(n).solve_with_seed(n as u64).unwrap();
+            assert!(check_board(&board).is_solved, "n = {}", n);
+        }
+    }
+}
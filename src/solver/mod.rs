@@ -1,17 +1,50 @@
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 
 use checker::{check_board, CheckResult};
 use position::CoordIter;
 use queen::get_contested_spaces;
-use {Board, CoordList, PosCoords};
+use {Board, CoordList, Piece, PosCoords, Topology};
+
+mod bitboard_search;
+mod count_solutions;
+mod domination;
+mod exhaustive_search;
+mod extension;
+mod fundamental_solutions;
+mod min_conflicts;
+mod parallel_search;
+mod search_mode;
+mod solution_iter;
+mod solution_set;
+mod square_search;
+
+use self::bitboard_search::BitboardSearch;
+
+pub use self::count_solutions::count_solutions;
+pub use self::domination::{Domination, DominationSolver};
+pub use self::extension::{extend, extend_all, Extension};
+pub use self::fundamental_solutions::FundamentalSolution;
+pub use self::min_conflicts::MinConflictsSolver;
+pub use self::search_mode::SearchMode;
+pub use self::solution_iter::Solutions;
+pub use self::solution_set::SolutionSet;
 
 /// This struct is used to find solutions to the problem, given a board state.
 #[derive(Clone, Debug)]
 pub struct Solver {
     solutions: HashSet<CoordList>,
+    pending: VecDeque<CoordList>,
     state_heap: Vec<CoordList>,
+    blocked: CoordList,
+    piece: Piece,
+    topology: Topology,
     visited: HashSet<Board>,
+    max_visited: Option<usize>,
+    num_pruned: u64,
+    start: CoordList,
     dimensions: PosCoords,
+    mode: SearchMode,
+    bitboard: Option<BitboardSearch>,
 }
 
 impl Solver {
@@ -22,15 +55,53 @@ impl Solver {
         state_heap.push(default_board);
         Solver {
             state_heap,
+            blocked: CoordList::new(),
+            piece: Piece::Queen,
+            topology: Topology::Flat,
             visited: HashSet::new(),
+            max_visited: None,
+            num_pruned: 0,
+            start: CoordList::new(),
             solutions: HashSet::new(),
+            pending: VecDeque::new(),
             dimensions: (8, 8),
+            mode: SearchMode::default(),
+            bitboard: None,
         }
     }
 
+    /// Return the search mode used by the solver.
+    pub fn search_mode(&self) -> SearchMode {
+        self.mode
+    }
+
+    /// Set the search mode used by the solver. Use `SearchMode::Exhaustive`
+    /// when every solution must be found.
+    pub fn set_search_mode(&mut self, mode: SearchMode) {
+        self.mode = mode;
+    }
+
+    /// Set the maximum number of states kept in the visited table. Once the
+    /// table is full, new states are still expanded, but are no longer
+    /// recorded. `None` means that the table is unbounded.
+    pub fn set_max_visited(&mut self, max_visited: Option<usize>) {
+        self.max_visited = max_visited;
+    }
+
+    /// Return the number of states recorded in the visited table.
+    pub fn num_visited(&self) -> usize {
+        self.visited.len()
+    }
+
+    /// Return the number of states that were skipped, because the state (or
+    /// one of its symmetries) had already been visited.
+    pub fn num_pruned(&self) -> u64 {
+        self.num_pruned
+    }
+
     /// Returns true if the solver is done examining moves.
     pub fn is_done(&self) -> bool {
-        self.state_heap.is_empty()
+        self.state_heap.is_empty() && self.bitboard.is_none()
     }
 
     /// Returns true if the solver has found at least one solution.
@@ -38,19 +109,46 @@ impl Solver {
         !self.solutions.is_empty()
     }
 
-    /// Tick the solver forward one iteration. Attempt to pop an item off of
-    /// the state heap, and check if it is a solution. If it is a solution,
-    /// add it (and its reflections) to the solutions set. If it is not a
-    /// solution, add it (and its reflections) to the visited set, then
-    /// calculate the next possible moves.
+    /// Tick the solver forward one iteration, using the current search mode.
+    /// Boards with blocked squares, or of pieces that do not attack along
+    /// rows and columns, are always searched square by square, as the other
+    /// searches assume that each line holds at most one piece. The heuristic
+    /// search is only used for queens on a flat board, and other boards are
+    /// searched exhaustively instead.
     pub fn tick(&mut self) {
+        if self.uses_square_search() {
+            return self.tick_squares();
+        }
+        let is_flat_queens = self.piece == Piece::Queen && self.topology == Topology::Flat;
+        match self.mode {
+            SearchMode::Heuristic if is_flat_queens => self.tick_heuristic(),
+            SearchMode::Heuristic | SearchMode::Exhaustive => self.tick_exhaustive(),
+            SearchMode::Bitboard => self.tick_bitboard(),
+        }
+    }
+
+    /// Returns true if the board must be searched square by square, rather
+    /// than line by line.
+    fn uses_square_search(&self) -> bool {
+        !self.blocked.is_empty() || !self.piece.holds_one_per_line()
+    }
+
+    /// Attempt to pop an item off of the state heap, and skip it if it (or
+    /// one of its symmetries) has already been visited. Otherwise, check if
+    /// it is a solution. If it is a solution, add it (and its symmetries) to
+    /// the solutions set. If it is not a solution, calculate the next
+    /// possible moves.
+    fn tick_heuristic(&mut self) {
         if let Some(queen_positions) = self.state_heap.pop() {
-            let board = queen_positions.iter().cloned().collect::<Board>();
-            self.add_state_and_reflections_to_visited(&board);
+            let board = Board::with_queens(self.dimensions, queen_positions.iter().cloned());
+            if !self.add_state_to_visited(&board) {
+                self.num_pruned += 1;
+                return;
+            }
             match check_board(&board) {
                 // Process a solution.
                 CheckResult { is_solved, .. } if is_solved => {
-                    self.solutions.insert(queen_positions);
+                    self.add_solution_and_symmetries(&board);
                 }
                 // Find next moves.
                 CheckResult {
@@ -67,27 +165,51 @@ impl Solver {
         }
     }
 
-    /// Progress until the next solution is found.
-    pub fn get_next_solution(&mut self) {
-        let initial_soln_count = self.solutions.len();
-        while self.solutions.len() == initial_soln_count {
+    /// Progress until the next solution is found, and return it. Each
+    /// solution is only returned once. Returns `None` once the search space
+    /// has been exhausted. Every solution returned is kept in the solutions
+    /// set, so memory use still grows with the number of solutions found.
+    pub fn get_next_solution(&mut self) -> Option<Board> {
+        loop {
+            if let Some(queen_positions) = self.pending.pop_front() {
+                return Some(self.create_board(queen_positions));
+            }
+            if self.is_done() {
+                return None;
+            }
             self.tick();
         }
     }
 
-    /// Find all of the solutions to the eight queen problem.
+    /// Find all of the solutions to the eight queen problem. The solutions
+    /// returned here are not returned by `get_next_solution` afterwards.
     pub fn solve(&mut self) -> HashSet<CoordList> {
         while !self.is_done() {
             self.tick();
         }
+        self.pending.clear();
         self.solutions.clone()
     }
 
+    /// Run the search to completion, and return the number of solutions
+    /// found. In bitboard mode, the remaining solutions are counted without
+    /// being stored in the solutions set.
+    pub fn count(&mut self) -> u64 {
+        let mut uncounted = 0;
+        while let Some(mut search) = self.take_bitboard_search() {
+            uncounted += search.count_remaining();
+        }
+        while !self.is_done() {
+            self.tick();
+        }
+        self.solutions.len() as u64 + uncounted
+    }
+
     /// Get the next best moves from the board state, given as a list of
     /// position coordinates.
     fn get_next_moves(&self, queen_positions: CoordList) -> Vec<CoordList> {
-        let board = queen_positions.iter().cloned().collect::<Board>();
-        let contested: HashSet<PosCoords> = get_contested_spaces(queen_positions, self.dimensions)
+        let board = self.create_board(queen_positions.clone());
+        let contested: HashSet<PosCoords> = get_contested_spaces(queen_positions, &board)
             .iter()
             .cloned()
             .collect();
@@ -119,12 +241,59 @@ impl Solver {
         unimplemented!();
     }
 
-    /// Add a board and its equivalent reflections to the visisted table.
-    fn add_state_and_reflections_to_visited(&mut self, board: &Board) {
-        self.visited.insert(board.clone());
-        board.get_reflections().into_iter().for_each(|board| {
-            self.visited.insert(board);
-        });
+    /// Add the canonical form of a board to the visited table, so that the
+    /// board and its symmetries are only expanded once. Returns false if the
+    /// board had already been visited.
+    fn add_state_to_visited(&mut self, board: &Board) -> bool {
+        let canonical = board.canonical_form();
+        if self.visited.contains(&canonical) {
+            return false;
+        }
+        let is_full = self.max_visited
+            .is_some_and(|max| self.visited.len() >= max);
+        if !is_full {
+            self.visited.insert(canonical);
+        }
+        true
+    }
+
+    /// Returns true if pieces at positions `a` and `b` attack each other on
+    /// the board being solved.
+    fn attacks(&self, a: PosCoords, b: PosCoords) -> bool {
+        self.piece
+            .attacks(a, b, self.dimensions, &self.blocked, self.topology)
+    }
+
+    /// Create a board holding the given pieces, along with the blocked squares,
+    /// the kind of piece, and the topology of the board being solved.
+    fn create_board(&self, queen_positions: CoordList) -> Board {
+        let mut board = Board::with_queens(self.dimensions, queen_positions);
+        board.set_piece(self.piece);
+        board.set_topology(self.topology);
+        for &pos in self.blocked.iter() {
+            board.add_blocked(pos);
+        }
+        board
+    }
+
+    /// Add a solution to the solutions set. If it had not been found before,
+    /// it is also queued to be returned by `get_next_solution`.
+    fn add_solution(&mut self, queen_positions: CoordList) {
+        if self.solutions.insert(queen_positions.clone()) {
+            self.pending.push_back(queen_positions);
+        }
+    }
+
+    /// Add a solution to the solutions set, along with each of its symmetries
+    /// that extend the starting state. These are the solutions that would
+    /// have been found from the symmetric states that were pruned.
+    fn add_solution_and_symmetries(&mut self, board: &Board) {
+        for (_, image) in board.get_symmetries() {
+            let queen_positions = image.get_queen_positions();
+            if self.start.iter().all(|pos| queen_positions.contains(pos)) {
+                self.add_solution(queen_positions);
+            }
+        }
     }
 }
 
@@ -134,9 +303,18 @@ impl From<Board> for Solver {
         state_heap.push(board.get_queen_positions());
         Solver {
             state_heap,
+            blocked: board.get_blocked_positions(),
+            piece: board.piece(),
+            topology: board.topology(),
             visited: HashSet::new(),
+            max_visited: None,
+            num_pruned: 0,
+            start: board.get_queen_positions(),
             solutions: HashSet::new(),
+            pending: VecDeque::new(),
             dimensions: board.dims(),
+            mode: SearchMode::default(),
+            bitboard: None,
         }
     }
 }
@@ -148,8 +326,11 @@ mod cummulative_tick_bench {
     use self::test::Bencher;
     use super::Solver;
     use rand::Rng;
+    use random::seeded_rng;
     use {Board, PosCoords};
 
+    const RNG_SEED: u64 = 4;
+
     #[bench]
     fn time_1_tick_for_empty_board(bencher: &mut Bencher) {
         let mut s = Solver::new();
@@ -220,7 +401,7 @@ mod cummulative_tick_bench {
     }
 
     fn get_n_random_coords(n: usize) -> Vec<PosCoords> {
-        let mut rng = rand::thread_rng();
+        let mut rng = seeded_rng(RNG_SEED);
         let mut y_range = (0..8).collect::<Vec<u32>>();
         let mut x_range = (0..8).collect::<Vec<u32>>();
         rng.shuffle(&mut x_range);
@@ -334,6 +515,29 @@ mod solve_tests {
     }
 }
 
+#[cfg(test)]
+mod board_dimension_tests {
+    use super::Solver;
+    use Board;
+
+    #[test]
+    fn heuristic_search_uses_solver_dimensions() {
+        let mut solver = Solver::from(Board::from((6, 6)));
+        let solutions = solver.solve();
+        assert_eq!(solutions.len(), 4);
+        solutions.iter().for_each(|soln| {
+            assert_eq!(soln.len(), 6);
+            assert!(soln.iter().all(|&(x, y)| x < 6 && y < 6));
+        });
+    }
+
+    #[test]
+    fn heuristic_search_solves_non_square_boards() {
+        let mut solver = Solver::from(Board::from((4, 2)));
+        assert_eq!(solver.solve().len(), 6);
+    }
+}
+
 #[cfg(test)]
 mod solve_benches {
     extern crate test;
@@ -411,3 +615,54 @@ mod solve_benches {
     //     });
     // }
 }
+
+#[cfg(test)]
+mod visited_pruning_tests {
+    use super::{SearchMode, Solver};
+    use Board;
+
+    fn create_board() -> Board {
+        [(2, 0), (4, 1), (1, 2)].iter().cloned().collect()
+    }
+
+    #[test]
+    fn revisited_states_are_pruned() {
+        let mut solver = Solver::from(create_board());
+        solver.solve();
+        assert!(solver.num_pruned() > 0);
+        assert!(solver.num_visited() > 0);
+    }
+
+    #[test]
+    fn pruning_keeps_solutions_that_extend_the_start() {
+        let mut solver = Solver::from(create_board());
+        let solutions = solver.solve();
+        let mut exhaustive = Solver::from(create_board());
+        exhaustive.set_search_mode(SearchMode::Exhaustive);
+        let expected = exhaustive.solve();
+        assert!(!solutions.is_empty());
+        assert!(solutions.is_subset(&expected));
+    }
+
+    #[test]
+    fn visited_table_respects_maximum_size() {
+        let mut solver = Solver::from(create_board());
+        solver.set_max_visited(Some(10));
+        let solutions = solver.solve();
+        assert_eq!(solver.num_visited(), 10);
+        assert!(!solutions.is_empty());
+    }
+
+    #[test]
+    fn solutions_include_symmetries_for_empty_start() {
+        let mut solver = Solver::new();
+        while !solver.solution_exists() {
+            solver.tick();
+        }
+        let solution = solver.solutions.iter().next().unwrap().clone();
+        let board: Board = solution.into_iter().collect();
+        board.get_symmetries().into_iter().for_each(|(_, image)| {
+            assert!(solver.solutions.contains(&image.get_queen_positions()));
+        });
+    }
+}
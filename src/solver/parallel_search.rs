@@ -0,0 +1,206 @@
+use std::thread;
+
+use super::{SearchMode, Solver};
+use {CoordList, StateSet};
+
+/// The number of states per worker thread to aim for when splitting the
+/// search tree. Using more states than threads helps balance the work, as
+/// some branches of the search tree are much larger than others.
+const STATES_PER_WORKER: usize = 4;
+
+/// The maximum number of rows to expand when splitting the search tree.
+const MAX_SPLIT_DEPTH: usize = 2;
+
+impl Solver {
+    /// Find all of the solutions, splitting the search across the given
+    /// number of worker threads. The parallel search is always exhaustive,
+    /// so the merged result is equal to the result of `solve` in
+    /// `SearchMode::Exhaustive`, regardless of the number of threads. Boards
+    /// that are searched square by square, such as boards with blocked
+    /// squares, are searched on the current thread. In heuristic mode, the
+    /// states already pruned as symmetries are only covered by the symmetries
+    /// of the solutions found, so these are added for each worker's solution.
+    pub fn solve_parallel(&mut self, num_threads: usize) -> StateSet {
+        if self.uses_square_search() {
+            return self.solve();
+        }
+        self.finish_bitboard_search();
+        let handles = self.spawn_workers(num_threads, |mut worker| worker.solve());
+        for handle in handles {
+            let solutions = handle.join().expect("Solver worker thread panicked");
+            match self.mode {
+                SearchMode::Heuristic => {
+                    for queen_positions in solutions {
+                        let board = self.create_board(queen_positions);
+                        self.add_solution_and_symmetries(&board);
+                    }
+                }
+                _ => self.solutions.extend(solutions),
+            }
+        }
+        self.pending.clear();
+        self.solutions.clone()
+    }
+
+    /// Count all of the solutions, splitting the search across the given
+    /// number of worker threads. The result is equal to the result of
+    /// `count`, regardless of the number of threads. Boards that are searched
+    /// square by square are searched on the current thread. In heuristic
+    /// mode, the solutions set may already hold symmetries of solutions that
+    /// the workers will find, so the solutions are merged into the set by
+    /// `solve_parallel` rather than counted.
+    pub fn count_parallel(&mut self, num_threads: usize) -> u64 {
+        if self.uses_square_search() {
+            return self.count();
+        }
+        if self.mode == SearchMode::Heuristic {
+            return self.solve_parallel(num_threads).len() as u64;
+        }
+        let mut count = self.solutions.len() as u64;
+        if let Some(mut search) = self.bitboard.take() {
+            count += search.count_remaining();
+        }
+        let handles = self.spawn_workers(num_threads, |mut worker| worker.count());
+        for handle in handles {
+            count += handle.join().expect("Solver worker thread panicked");
+        }
+        count
+    }
+
+    /// Split the states on the state heap between the worker threads, and
+    /// spawn a solver for each that runs the given function.
+    fn spawn_workers<T, F>(&mut self, num_threads: usize, f: F) -> Vec<thread::JoinHandle<T>>
+    where
+        T: Send + 'static,
+        F: Fn(Solver) -> T + Send + Copy + 'static,
+    {
+        let num_threads = num_threads.max(1);
+        let mut worker_states = vec![Vec::new(); num_threads];
+        for (i, state) in self.split_states(num_threads).into_iter().enumerate() {
+            worker_states[i % num_threads].push(state);
+        }
+        worker_states
+            .into_iter()
+            .filter(|states| !states.is_empty())
+            .map(|states| {
+                let worker = self.create_worker(states);
+                thread::spawn(move || f(worker))
+            })
+            .collect()
+    }
+
+    /// Take the states off of the state heap, and expand them line by line
+    /// until there are enough states to share between the worker threads.
+    fn split_states(&mut self, num_threads: usize) -> Vec<CoordList> {
+        let mut states: Vec<CoordList> = self.state_heap.drain(..).collect();
+        for _ in 0..MAX_SPLIT_DEPTH {
+            if states.len() >= num_threads * STATES_PER_WORKER {
+                break;
+            }
+            states = states
+                .into_iter()
+                .flat_map(|state| match self.get_first_empty_line(&state) {
+                    Some(line) => self.get_line_moves(&state, line),
+                    None => vec![state],
+                })
+                .collect();
+        }
+        states
+    }
+
+    /// Create a solver for a worker thread, which will search from the
+    /// given states using an exhaustive search mode.
+    fn create_worker(&self, states: Vec<CoordList>) -> Solver {
+        let mut worker = Solver::from(self.create_board(CoordList::new()));
+        worker.state_heap = states;
+        worker.mode = match self.mode {
+            SearchMode::Bitboard => SearchMode::Bitboard,
+            _ => SearchMode::Exhaustive,
+        };
+        worker
+    }
+
+    /// Run the bitboard search that is in progress to completion, if there is
+    /// one, so that only the states on the state heap remain to be split.
+    fn finish_bitboard_search(&mut self) {
+        if let Some(mut search) = self.bitboard.take() {
+            while !search.is_done() {
+                if search.step() {
+                    self.add_solution(search.get_solution());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod parallel_search_tests {
+    use super::super::{SearchMode, Solver};
+    use Board;
+
+    fn create_solver(n: u32, mode: SearchMode) -> Solver {
+        let mut solver = Solver::from(Board::from((n, n)));
+        solver.set_search_mode(mode);
+        solver
+    }
+
+    #[test]
+    fn parallel_solutions_match_sequential_solutions() {
+        (1..9).for_each(|n| {
+            let expected = create_solver(n, SearchMode::Exhaustive).solve();
+            [SearchMode::Exhaustive, SearchMode::Bitboard]
+                .iter()
+                .for_each(|&mode| {
+                    (1..5).for_each(|num_threads| {
+                        let actual = create_solver(n, mode).solve_parallel(num_threads);
+                        assert_eq!(actual, expected, "n = {}, threads = {}", n, num_threads);
+                    });
+                });
+        });
+    }
+
+    #[test]
+    fn parallel_count_matches_sequential_count() {
+        let expected = create_solver(10, SearchMode::Bitboard).count();
+        (1..5).for_each(|num_threads| {
+            let actual = create_solver(10, SearchMode::Bitboard).count_parallel(num_threads);
+            assert_eq!(actual, expected);
+        });
+    }
+
+    #[test]
+    fn parallel_search_uses_exhaustive_mode_for_heuristic_solver() {
+        let mut solver = create_solver(8, SearchMode::Heuristic);
+        assert_eq!(solver.solve_parallel(2).len(), 92);
+    }
+
+    /// The heuristic search adds the symmetries of each solution it finds,
+    /// which the workers find again once the search is split, and the states
+    /// it has pruned are only covered by the symmetries of their solutions.
+    #[test]
+    fn parallel_count_does_not_count_heuristic_symmetries_twice() {
+        let mut solver = create_solver(8, SearchMode::Heuristic);
+        assert_eq!(solver.solutions().take(3).count(), 3);
+        assert_eq!(solver.count_parallel(2), 92);
+        let mut solver = create_solver(8, SearchMode::Heuristic);
+        assert_eq!(solver.solutions().take(3).count(), 3);
+        assert_eq!(solver.solve_parallel(3), create_solver(8, SearchMode::Exhaustive).solve());
+    }
+
+    #[test]
+    fn parallel_search_completes_partial_board() {
+        let b: Board = [(2, 0), (4, 1), (1, 2), (7, 3), (0, 4)]
+            .iter()
+            .cloned()
+            .collect();
+        let mut sequential = Solver::from(b.clone());
+        sequential.set_search_mode(SearchMode::Exhaustive);
+        let mut parallel = Solver::from(b);
+        assert_eq!(parallel.solve_parallel(3), sequential.solve());
+    }
+
+    #[test]
+    fn parallel_search_with_zero_threads_uses_one_thread() {
+        assert_eq!(create_solver(6, SearchMode::Bitboard).count_parallel(0), 4);
+    }
+}
@@ -0,0 +1,19 @@
+/// The strategy used by a `Solver` when expanding board states.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SearchMode {
+    /// Expand the best ranked moves from each state first. This is fast at
+    /// finding a solution, but only the top ranked moves are kept, so the
+    /// resulting solution set is not guaranteed to be complete.
+    #[default]
+    Heuristic,
+    /// Row-by-row backtracking. Each state is extended by placing a queen in
+    /// every safe square of its lowest empty row, so every solution is
+    /// reached exactly once. Boards that are taller than they are wide are
+    /// searched column-by-column instead.
+    Exhaustive,
+    /// Row-by-row backtracking, where the contested columns and diagonals
+    /// are represented as integer bitmasks. This is exhaustive, and much
+    /// faster than `Exhaustive`, but is limited to boards with sides of up
+    /// to 64 squares. Larger boards fall back to `Exhaustive`.
+    Bitboard,
+}
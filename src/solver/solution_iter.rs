@@ -0,0 +1,87 @@
+use super::Solver;
+use Board;
+
+/// An iterator over the solutions found by a solver, created by
+/// `Solver::solutions`. Each solution is found lazily, by ticking the solver
+/// forward using its current search mode, and is only yielded once. The
+/// iterator ends once the search space has been exhausted.
+///
+/// Finding solutions lazily saves time, not memory. Each solution yielded is
+/// also kept in the solver's solutions set, and in heuristic mode the
+/// symmetries of each solution wait in a queue until they are yielded, so
+/// memory use is O(solutions found). Use `count_solutions` to count the
+/// solutions of a large board without storing them.
+pub struct Solutions<'a> {
+    solver: &'a mut Solver,
+}
+
+impl Solver {
+    /// Return an iterator that yields each new solution as it is found.
+    /// Solutions are still recorded in the solutions set, so that
+    /// duplicates can be skipped, and they are included in `solve`. This
+    /// means that the memory used by the solver grows with each solution.
+    pub fn solutions(&mut self) -> Solutions<'_> {
+        Solutions { solver: self }
+    }
+}
+
+impl<'a> Iterator for Solutions<'a> {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        self.solver.get_next_solution()
+    }
+}
+
+#[cfg(test)]
+mod solution_iter_tests {
+    use super::super::{SearchMode, Solver};
+    use std::collections::HashSet;
+    use Board;
+
+    fn create_solver(n: u32, mode: SearchMode) -> Solver {
+        let mut solver = Solver::from(Board::from((n, n)));
+        solver.set_search_mode(mode);
+        solver
+    }
+
+    #[test]
+    fn iterator_yields_each_solution_once() {
+        for &mode in [SearchMode::Exhaustive, SearchMode::Bitboard].iter() {
+            let boards = create_solver(8, mode).solutions().collect::<Vec<Board>>();
+            let unique = boards.iter().cloned().collect::<HashSet<Board>>();
+            assert_eq!(boards.len(), 92);
+            assert_eq!(unique.len(), 92);
+        }
+    }
+
+    #[test]
+    fn heuristic_iterator_does_not_yield_duplicate_symmetries() {
+        let boards = create_solver(6, SearchMode::Heuristic)
+            .solutions()
+            .collect::<Vec<Board>>();
+        assert_eq!(boards.len(), 4);
+        assert!(boards.iter().all(|board| board.dims() == (6, 6)));
+    }
+
+    #[test]
+    fn iterator_ends_when_no_solution_exists() {
+        let mut solver = create_solver(3, SearchMode::Exhaustive);
+        assert_eq!(solver.get_next_solution(), None);
+        assert_eq!(solver.solutions().next(), None);
+        assert!(solver.is_done());
+    }
+
+    #[test]
+    fn iterator_can_be_mixed_with_solve() {
+        let mut solver = create_solver(8, SearchMode::Bitboard);
+        let first = solver.solutions().take(10).collect::<HashSet<Board>>();
+        assert_eq!(first.len(), 10);
+        let solutions = solver.solve();
+        assert_eq!(solutions.len(), 92);
+        assert!(first
+            .iter()
+            .all(|board| solutions.contains(&board.get_queen_positions())));
+        assert_eq!(solver.get_next_solution(), None);
+    }
+}
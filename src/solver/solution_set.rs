@@ -0,0 +1,278 @@
+use super::Solver;
+use {Board, CoordList, Piece, PosCoords, StateSet, Topology};
+
+/// A set of solutions for a board with the given dimensions. Unlike a
+/// `StateSet`, the solutions are kept sorted by their queen positions, so
+/// that they have a stable order when they are printed or serialized. Sets
+/// found by solving a board with blocked squares also hold those squares, and
+/// sets found by solving a board of other pieces, or a toroidal board, hold
+/// the kind of piece and the topology.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SolutionSetData"))]
+pub struct SolutionSet {
+    width: u32,
+    height: u32,
+    solutions: Vec<CoordList>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    blocked: CoordList,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Piece::is_queen"))]
+    piece: Piece,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Topology::is_flat"))]
+    topology: Topology,
+}
+
+impl SolutionSet {
+    /// Create a sorted set of solutions for a board of the given dimensions.
+    pub fn new(dims: PosCoords, solutions: StateSet) -> SolutionSet {
+        let mut solutions = solutions.into_iter().collect::<Vec<CoordList>>();
+        solutions.sort();
+        SolutionSet {
+            width: dims.0,
+            height: dims.1,
+            solutions,
+            blocked: CoordList::new(),
+            piece: Piece::Queen,
+            topology: Topology::Flat,
+        }
+    }
+
+    /// Return a pair of coordinates representing the dimensions of the board.
+    pub fn dims(&self) -> PosCoords {
+        (self.width, self.height)
+    }
+
+    /// Return the number of solutions in the set.
+    pub fn len(&self) -> usize {
+        self.solutions.len()
+    }
+
+    /// Returns true if the set does not contain any solutions.
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+
+    /// Return the queen positions of each solution, in sorted order.
+    pub fn solutions(&self) -> &[CoordList] {
+        &self.solutions
+    }
+
+    /// Return the positions of the blocked squares of the solved board.
+    pub fn blocked(&self) -> &[PosCoords] {
+        &self.blocked
+    }
+
+    /// Return the kind of piece placed by each solution.
+    pub fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    /// Return the topology of the solved board.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Return a board for each solution, in sorted order.
+    pub fn boards(&self) -> Vec<Board> {
+        let dims = self.dims();
+        self.solutions
+            .iter()
+            .map(|queen_positions| {
+                let mut board = Board::with_queens(dims, queen_positions.clone());
+                board.set_piece(self.piece);
+                board.set_topology(self.topology);
+                for &pos in self.blocked.iter() {
+                    board.add_blocked(pos);
+                }
+                board
+            })
+            .collect()
+    }
+}
+
+impl From<SolutionSet> for StateSet {
+    fn from(set: SolutionSet) -> StateSet {
+        set.solutions.into_iter().collect()
+    }
+}
+
+impl Solver {
+    /// Find the solutions using the current search mode, and return them as
+    /// a sorted `SolutionSet`.
+    pub fn solve_sorted(&mut self) -> SolutionSet {
+        let dims = self.dimensions;
+        SolutionSet {
+            blocked: self.blocked.clone(),
+            piece: self.piece,
+            topology: self.topology,
+            ..SolutionSet::new(dims, self.solve())
+        }
+    }
+}
+
+/// The serialized form of a solution set. Each solution is validated by
+/// adding its pieces to a board holding the blocked squares, and the
+/// solutions are sorted and deduplicated.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct SolutionSetData {
+    width: u32,
+    height: u32,
+    solutions: Vec<CoordList>,
+    #[serde(default)]
+    blocked: CoordList,
+    #[serde(default)]
+    piece: Piece,
+    #[serde(default)]
+    topology: Topology,
+}
+
+#[cfg(feature = "serde")]
+impl ::std::convert::TryFrom<SolutionSetData> for SolutionSet {
+    type Error = ::BoardError;
+
+    fn try_from(data: SolutionSetData) -> Result<SolutionSet, ::BoardError> {
+        let dims = (data.width, data.height);
+        let mut empty = Board::from(dims);
+        empty.set_piece(data.piece);
+        empty.set_topology(data.topology);
+        for &pos in data.blocked.iter() {
+            empty.try_add_blocked(pos)?;
+        }
+        let solutions = data
+            .solutions
+            .into_iter()
+            .map(|queen_positions| {
+                let mut board = empty.clone();
+                for pos in queen_positions {
+                    board.try_add_queen(pos)?;
+                }
+                Ok(board.get_queen_positions())
+            })
+            .collect::<Result<StateSet, ::BoardError>>()?;
+        Ok(SolutionSet {
+            blocked: empty.get_blocked_positions(),
+            piece: data.piece,
+            topology: data.topology,
+            ..SolutionSet::new(dims, solutions)
+        })
+    }
+}
+
+#[cfg(test)]
+mod solution_set_tests {
+    use super::super::{SearchMode, Solver};
+    use super::SolutionSet;
+    use {Board, Piece, StateSet, Topology};
+
+    fn solve_sorted(n: u32) -> SolutionSet {
+        let mut solver = Solver::from(Board::from((n, n)));
+        solver.set_search_mode(SearchMode::Bitboard);
+        solver.solve_sorted()
+    }
+
+    #[test]
+    fn solutions_are_sorted() {
+        let set = solve_sorted(6);
+        assert_eq!(set.len(), 4);
+        assert!(set.solutions().windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn boards_have_the_set_dimensions() {
+        let set = solve_sorted(5);
+        assert!(set.boards().iter().all(|board| board.dims() == (5, 5)));
+    }
+
+    #[test]
+    fn boards_keep_blocked_squares() {
+        let mut solver = Solver::from(Board::with_blocked((3, 3), vec![(1, 0)]));
+        let set = solver.solve_sorted();
+        assert_eq!(set.blocked(), &[(1, 0)]);
+        assert!(set.boards().iter().all(|board| board.is_blocked(&(1, 0))));
+    }
+
+    #[test]
+    fn boards_keep_the_piece() {
+        let mut b = Board::from((4, 4));
+        b.set_piece(Piece::Knight);
+        let set = Solver::from(b).solve_sorted();
+        assert_eq!(set.piece(), Piece::Knight);
+        assert!(set.boards().iter().all(|board| board.piece() == Piece::Knight));
+    }
+
+    #[test]
+    fn boards_keep_the_topology() {
+        let mut b = Board::from((5, 5));
+        b.set_topology(Topology::Toroidal);
+        let set = Solver::from(b).solve_sorted();
+        assert_eq!(set.topology(), Topology::Toroidal);
+        assert!(set.boards().iter().all(|board| board.topology() == Topology::Toroidal));
+    }
+
+    #[test]
+    fn solution_set_converts_into_state_set() {
+        let mut solver = Solver::from(Board::from((6, 6)));
+        solver.set_search_mode(SearchMode::Bitboard);
+        let expected = solver.solve();
+        assert_eq!(StateSet::from(solve_sorted(6)), expected);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod solution_set_serde_tests {
+    use super::SolutionSet;
+    use serde_json;
+    use {Piece, StateSet};
+
+    #[test]
+    fn solution_set_round_trip_works() {
+        let set = SolutionSet::new((4, 4), StateSet::new());
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, r#"{"width":4,"height":4,"solutions":[]}"#);
+        let solutions = [
+            vec![(0, 2), (1, 0), (2, 3), (3, 1)],
+            vec![(0, 1), (1, 3), (2, 0), (3, 2)],
+        ];
+        let set = SolutionSet::new((4, 4), solutions.iter().cloned().collect());
+        let json = serde_json::to_string(&set).unwrap();
+        let expected = r#"{"width":4,"height":4,"solutions":[[[0,1],[1,3],[2,0],[3,2]],[[0,2],[1,0],[2,3],[3,1]]]}"#;
+        assert_eq!(json, expected);
+        assert_eq!(serde_json::from_str::<SolutionSet>(&json).unwrap(), set);
+    }
+
+    #[test]
+    fn solutions_with_invalid_positions_are_rejected() {
+        let json = r#"{"width":4,"height":4,"solutions":[[[0,1],[1,4]]]}"#;
+        assert!(serde_json::from_str::<SolutionSet>(json).is_err());
+        let json = r#"{"width":3,"height":3,"solutions":[[[1,0]]],"blocked":[[1,0]]}"#;
+        assert!(serde_json::from_str::<SolutionSet>(json).is_err());
+    }
+
+    #[test]
+    fn blocked_squares_are_serialized() {
+        let json = r#"{"width":3,"height":3,"solutions":[[[0,0],[1,2],[2,0]]],"blocked":[[1,0]]}"#;
+        let set = serde_json::from_str::<SolutionSet>(json).unwrap();
+        assert_eq!(set.blocked(), &[(1, 0)]);
+        assert_eq!(serde_json::to_string(&set).unwrap(), json);
+    }
+
+    #[test]
+    fn pieces_are_serialized() {
+        let json = r#"{"width":2,"height":2,"solutions":[[[0,0],[0,1],[1,0],[1,1]]],"piece":"Knight"}"#;
+        let set = serde_json::from_str::<SolutionSet>(json).unwrap();
+        assert_eq!(set.piece(), Piece::Knight);
+        assert_eq!(serde_json::to_string(&set).unwrap(), json);
+        let json = r#"{"width":2,"height":2,"solutions":[[[0,0],[0,1],[1,0]]]}"#;
+        assert!(serde_json::from_str::<SolutionSet>(json).is_err());
+    }
+
+    /// The capacity of a large toroidal knight board is found without
+    /// searching the board.
+    #[test]
+    fn large_toroidal_sets_are_validated() {
+        let json = r#"{"width":13,"height":13,"solutions":[[[0,0],[1,0]]],"piece":"Knight","topology":"Toroidal"}"#;
+        let set = serde_json::from_str::<SolutionSet>(json).unwrap();
+        assert_eq!(serde_json::to_string(&set).unwrap(), json);
+    }
+}
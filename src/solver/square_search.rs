@@ -0,0 +1,331 @@
+use super::Solver;
+use piece::Line;
+use {CoordList, Piece, PosCoords};
+
+impl Solver {
+    /// Tick the solver forward one iteration, placing pieces square by square
+    /// in row-major order. This is used for boards with blocked squares, as a
+    /// blocked square splits its lines into segments that can each hold a
+    /// piece, and for pieces such as bishops and knights that can share a
+    /// row. Pop a state off of the state heap. If it holds the target number
+    /// of pieces, it is a solution. Otherwise, push a new state for each safe
+    /// square after the last piece placed, unless those squares cannot fit
+    /// enough pieces.
+    pub(super) fn tick_squares(&mut self) {
+        if let Some(queen_positions) = self.state_heap.pop() {
+            let target = self.piece.max_independent(self.dimensions, self.topology) as usize;
+            if queen_positions.len() >= target {
+                if queen_positions.len() == target && !self.has_square_conflict(&queen_positions) {
+                    self.add_solution(queen_positions);
+                }
+                return;
+            }
+            let candidates = self.get_candidate_squares(&queen_positions);
+            if queen_positions.len() + self.max_new_pieces(&candidates) < target {
+                return;
+            }
+            let next_moves = candidates.into_iter().rev().map(|pos| {
+                let mut next = queen_positions.clone();
+                next.push(pos);
+                next.sort();
+                next
+            });
+            self.state_heap.extend(next_moves);
+        }
+    }
+
+    /// Return the squares, in row-major order, that come after the last piece
+    /// placed by the search, and that are not blocked or attacked.
+    fn get_candidate_squares(&self, queen_positions: &[PosCoords]) -> CoordList {
+        let (width, height) = self.dimensions;
+        let last = queen_positions
+            .iter()
+            .filter(|pos| !self.start.contains(pos))
+            .map(|&(x, y)| (y, x))
+            .max();
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| last.is_none_or(|last| (y, x) > last))
+            .filter(|pos| self.blocked.binary_search(pos).is_err())
+            .filter(|&pos| {
+                !queen_positions
+                    .iter()
+                    .any(|&queen| self.attacks(queen, pos))
+            })
+            .collect()
+    }
+
+    /// Return an upper bound on the number of pieces that can be added to the
+    /// candidate squares. Each segment of a line that the piece slides along,
+    /// between blocked squares, can hold at most one piece, and so can each
+    /// 2x2 block of squares for kings.
+    fn max_new_pieces(&self, candidates: &[PosCoords]) -> usize {
+        let count_distinct = |mut keys: Vec<(usize, usize)>| {
+            keys.sort();
+            keys.dedup();
+            keys.len()
+        };
+        let line_bounds = self.piece.lines().iter().map(|&line| {
+            let segments = candidates
+                .iter()
+                .map(|&pos| self.get_segment(line, pos))
+                .collect();
+            count_distinct(segments)
+        });
+        let block_bound = match self.piece {
+            Piece::King => candidates
+                .iter()
+                .map(|&(x, y)| (x as usize / 2, y as usize / 2))
+                .collect(),
+            _ => candidates.iter().map(|&(x, y)| (x as usize, y as usize)).collect(),
+        };
+        line_bounds.fold(count_distinct(block_bound), |bound, n| bound.min(n))
+    }
+
+    /// Return the index of the line of the given family that passes through a
+    /// position, paired with the number of blocked squares before it on that
+    /// line. Positions that share a segment of the line have the same result.
+    fn get_segment(&self, line: Line, pos: PosCoords) -> (usize, usize) {
+        let (index, offset) = line.locate(pos, self.dimensions);
+        let before = self.blocked.iter().filter(|&&square| {
+            let (square_index, square_offset) = line.locate(square, self.dimensions);
+            square_index == index && square_offset < offset
+        });
+        (index, before.count())
+    }
+
+    /// Returns true if any pair of pieces in the list attack each other. Moves
+    /// generated by the search are always safe, so this is only needed to
+    /// reject a conflicting starting state.
+    fn has_square_conflict(&self, queen_positions: &[PosCoords]) -> bool {
+        queen_positions.iter().enumerate().any(|(i, &a)| {
+            queen_positions[i + 1..]
+                .iter()
+                .any(|&b| self.attacks(a, b))
+        })
+    }
+}
+
+#[cfg(test)]
+mod square_search_tests {
+    use super::super::{SearchMode, Solver};
+    use checker::check_board;
+    use position::CoordIter;
+    use {Board, Piece, Topology};
+
+    fn solve(board: Board, mode: SearchMode) -> Vec<Board> {
+        let mut solver = Solver::from(board);
+        solver.set_search_mode(mode);
+        solver.solve_sorted().boards()
+    }
+
+    /// There is no solution for an empty 3x3 board, but blocking a square in
+    /// the first row allows it to hold two queens.
+    ///
+    ///   012
+    ///   ---
+    /// 2| Q |
+    /// 1|   |
+    /// 0|Q#Q|
+    ///   ---
+    #[test]
+    fn blocked_squares_allow_two_queens_in_a_line() {
+        let b = Board::with_blocked((3, 3), vec![(1, 0)]);
+        let mut expected = Board::with_queens((3, 3), vec![(0, 0), (1, 2), (2, 0)]);
+        expected.add_blocked((1, 0));
+        assert_eq!(solve(b, SearchMode::Exhaustive), vec![expected]);
+        let b = Board::with_blocked((3, 3), vec![(1, 1)]);
+        assert!(solve(b, SearchMode::Exhaustive).is_empty());
+    }
+
+    #[test]
+    fn every_search_mode_respects_blocked_squares() {
+        let b = Board::with_blocked((5, 5), vec![(2, 2)]);
+        let expected = solve(b.clone(), SearchMode::Exhaustive);
+        assert!(!expected.is_empty());
+        assert_eq!(solve(b.clone(), SearchMode::Heuristic), expected);
+        assert_eq!(solve(b.clone(), SearchMode::Bitboard), expected);
+        let mut solver = Solver::from(b.clone());
+        assert_eq!(solver.solve_parallel(2).len(), expected.len());
+        let mut solver = Solver::from(b);
+        solver.set_search_mode(SearchMode::Bitboard);
+        assert_eq!(solver.count(), expected.len() as u64);
+    }
+
+    /// Every solution found matches a brute force search over each set of
+    /// squares that could hold the queens.
+    #[test]
+    fn blocked_search_matches_brute_force() {
+        let b = Board::with_blocked((4, 4), vec![(1, 1), (2, 3)]);
+        let squares = (0..16)
+            .map(|i| (i % 4, i / 4))
+            .filter(|pos| !b.is_blocked(pos))
+            .collect::<Vec<_>>();
+        let mut expected = Vec::new();
+        for a in 0..squares.len() {
+            for c in a + 1..squares.len() {
+                for d in c + 1..squares.len() {
+                    for e in d + 1..squares.len() {
+                        let mut candidate = b.clone();
+                        for &i in [a, c, d, e].iter() {
+                            candidate.add_queen(squares[i]);
+                        }
+                        if check_board(&candidate).is_solved {
+                            expected.push(candidate);
+                        }
+                    }
+                }
+            }
+        }
+        expected.sort_by_key(|board| board.get_queen_positions());
+        assert_eq!(solve(b, SearchMode::Exhaustive), expected);
+    }
+
+    #[test]
+    fn blocked_search_completes_partial_board() {
+        let mut b = Board::with_blocked((3, 3), vec![(1, 0)]);
+        b.add_queen((1, 2));
+        let solutions = solve(b.clone(), SearchMode::Exhaustive);
+        let expected = vec![(0, 0), (1, 2), (2, 0)];
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(solutions[0].get_queen_positions(), expected);
+        let mut b = Board::with_blocked((3, 3), vec![(1, 0)]);
+        b.add_queen((0, 1));
+        assert!(solve(b, SearchMode::Exhaustive).is_empty());
+    }
+
+    fn solve_pieces(dims: (u32, u32), piece: Piece) -> Vec<Board> {
+        solve_pieces_with_topology(dims, piece, Topology::Flat)
+    }
+
+    fn solve_pieces_with_topology(
+        dims: (u32, u32),
+        piece: Piece,
+        topology: Topology,
+    ) -> Vec<Board> {
+        let mut b = Board::from(dims);
+        b.set_piece(piece);
+        b.set_topology(topology);
+        solve(b, SearchMode::Exhaustive)
+    }
+
+    /// Find the solutions by checking every set of squares on a small board,
+    /// keeping the largest sets in which no two pieces attack each other. The
+    /// counts for queens, amazons and knights on toroidal boards with an odd
+    /// dimension are only an upper bound, so sets of that size may not exist.
+    fn brute_force(dims: (u32, u32), piece: Piece, topology: Topology) -> Vec<Board> {
+        let squares = CoordIter::from(dims).collect::<Vec<_>>();
+        let attacks = squares
+            .iter()
+            .map(|&a| {
+                (0..squares.len())
+                    .filter(|&i| piece.attacks(a, squares[i], dims, &[], topology))
+                    .fold(0u32, |mask, i| mask | 1 << i)
+            })
+            .collect::<Vec<u32>>();
+        let is_independent = |mask: u32| {
+            (0..squares.len()).all(|i| mask & (1 << i) == 0 || mask & attacks[i] == 1 << i)
+        };
+        let independent = (0u32..1 << squares.len())
+            .filter(|&mask| is_independent(mask))
+            .collect::<Vec<u32>>();
+        let largest = independent.iter().map(|mask| mask.count_ones()).max().unwrap();
+        let target = piece.max_independent(dims, topology);
+        assert!(largest <= target, "{:?} {:?} {:?}", piece, dims, topology);
+        let searched = piece.search_max_independent(dims, topology);
+        assert_eq!(largest, searched, "{:?} {:?} {:?}", piece, dims, topology);
+        let is_bound = match piece {
+            Piece::Queen | Piece::Amazon => true,
+            Piece::Knight => !topology.is_flat() && (dims.0 % 2 == 1 || dims.1 % 2 == 1),
+            _ => false,
+        };
+        if !is_bound {
+            assert_eq!(largest, target, "{:?} {:?} {:?}", piece, dims, topology);
+        }
+        let mut solutions = independent
+            .into_iter()
+            .filter(|mask| mask.count_ones() == target)
+            .map(|mask| {
+                let queens = (0..squares.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| squares[i]);
+                let mut b = Board::with_queens(dims, queens);
+                b.set_piece(piece);
+                b.set_topology(topology);
+                b
+            })
+            .filter(|b| check_board(b).is_solved)
+            .collect::<Vec<_>>();
+        solutions.sort_by_key(|board| board.get_queen_positions());
+        solutions
+    }
+
+    #[test]
+    fn pieces_are_counted_on_a_4x4_board() {
+        let expected = [
+            (Piece::Queen, 2),
+            (Piece::Rook, 24),
+            (Piece::Bishop, 16),
+            (Piece::Knight, 6),
+            (Piece::King, 79),
+            (Piece::Amazon, 0),
+        ];
+        for &(piece, count) in expected.iter() {
+            assert_eq!(solve_pieces((4, 4), piece).len(), count, "{:?}", piece);
+        }
+    }
+
+    #[test]
+    fn piece_search_matches_brute_force() {
+        for &piece in Piece::all() {
+            for &dims in [(3, 3), (4, 3), (2, 5), (2, 3), (3, 5), (4, 5)].iter() {
+                let expected = brute_force(dims, piece, Topology::Flat);
+                assert_eq!(solve_pieces(dims, piece), expected, "{:?} {:?}", piece, dims);
+            }
+        }
+    }
+
+    #[test]
+    fn toroidal_piece_search_matches_brute_force() {
+        for &piece in Piece::all() {
+            for &dims in [(3, 3), (4, 3), (2, 5), (4, 4), (3, 5), (4, 5)].iter() {
+                let expected = brute_force(dims, piece, Topology::Toroidal);
+                let solutions = solve_pieces_with_topology(dims, piece, Topology::Toroidal);
+                assert_eq!(solutions, expected, "{:?} {:?}", piece, dims);
+            }
+        }
+    }
+
+    /// Five kings fit on a 5x5 toroidal board, although nine fit on a flat
+    /// one.
+    #[test]
+    fn kings_are_solved_on_a_toroidal_board() {
+        let solutions = solve_pieces_with_topology((5, 5), Piece::King, Topology::Toroidal);
+        assert!(!solutions.is_empty());
+        for solution in solutions {
+            assert_eq!(solution.get_queen_positions().len(), 5);
+            assert!(check_board(&solution).is_solved);
+        }
+    }
+
+    /// Amazons cannot be placed on boards smaller than 10x10, which has four
+    /// solutions.
+    #[test]
+    fn amazons_are_solved_on_a_10x10_board() {
+        assert!(solve_pieces((9, 9), Piece::Amazon).is_empty());
+        assert_eq!(solve_pieces((10, 10), Piece::Amazon).len(), 4);
+    }
+
+    #[test]
+    fn pieces_complete_partial_boards() {
+        let mut b = Board::from((4, 4));
+        b.set_piece(Piece::Knight);
+        b.add_queen((0, 0));
+        let solutions = solve(b, SearchMode::Exhaustive);
+        assert!(!solutions.is_empty());
+        for solution in solutions {
+            assert!(solution.get_queen_positions().contains(&(0, 0)));
+            assert!(check_board(&solution).is_solved);
+        }
+    }
+}
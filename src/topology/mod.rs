@@ -0,0 +1,83 @@
+use position_types::PosCoords;
+
+/// The shape of the surface that a board is drawn on.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Topology {
+    /// An ordinary board, where moves stop at the edges.
+    #[default]
+    Flat,
+    /// A board where each edge is joined to the opposite edge, so that rows,
+    /// columns, and diagonals wrap around modulo the dimensions of the board.
+    /// Queens can only be placed on every row of an N x N toroidal board when
+    /// gcd(N, 6) = 1.
+    Toroidal,
+}
+
+impl Topology {
+    /// Returns true if the topology is flat. Boards on a flat surface are
+    /// serialized without their topology, as it is the default.
+    pub fn is_flat(&self) -> bool {
+        *self == Topology::Flat
+    }
+
+    /// Return the square reached by moving the given offset from a position.
+    /// On a flat board, returns `None` if the square is out of bounds. On a
+    /// toroidal board, the square wraps around the edges of the board.
+    pub fn step(self, pos: PosCoords, dims: PosCoords, (dx, dy): (i64, i64)) -> Option<PosCoords> {
+        let (x, y) = (pos.0 as i64 + dx, pos.1 as i64 + dy);
+        let (width, height) = (dims.0 as i64, dims.1 as i64);
+        match self {
+            Topology::Flat if x < 0 || y < 0 || x >= width || y >= height => None,
+            Topology::Flat => Some((x as u32, y as u32)),
+            Topology::Toroidal if width == 0 || height == 0 => None,
+            Topology::Toroidal => Some((x.rem_euclid(width) as u32, y.rem_euclid(height) as u32)),
+        }
+    }
+
+    /// Return the offset from `a` to `b`. On a toroidal board, the offset is
+    /// reduced modulo the dimensions, so that it is never negative.
+    pub fn offset(self, a: PosCoords, b: PosCoords, dims: PosCoords) -> (i64, i64) {
+        let (dx, dy) = (b.0 as i64 - a.0 as i64, b.1 as i64 - a.1 as i64);
+        match self {
+            Topology::Flat => (dx, dy),
+            Topology::Toroidal => (dx.rem_euclid(dims.0 as i64), dy.rem_euclid(dims.1 as i64)),
+        }
+    }
+}
+
+/// Return the greatest common divisor of two numbers.
+pub fn gcd(a: u32, b: u32) -> u32 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+#[cfg(test)]
+mod topology_tests {
+    use super::{gcd, Topology};
+
+    #[test]
+    fn flat_steps_stop_at_the_edges() {
+        assert_eq!(Topology::Flat.step((0, 0), (4, 3), (1, 2)), Some((1, 2)));
+        assert_eq!(Topology::Flat.step((0, 0), (4, 3), (-1, 0)), None);
+        assert_eq!(Topology::Flat.step((3, 2), (4, 3), (0, 1)), None);
+    }
+
+    #[test]
+    fn toroidal_steps_wrap_around() {
+        let torus = Topology::Toroidal;
+        assert_eq!(torus.step((0, 0), (4, 3), (-1, -1)), Some((3, 2)));
+        assert_eq!(torus.step((3, 2), (4, 3), (2, 7)), Some((1, 0)));
+        assert_eq!(torus.offset((3, 2), (0, 0), (4, 3)), (1, 1));
+        assert_eq!(Topology::Flat.offset((3, 2), (0, 0), (4, 3)), (-3, -2));
+    }
+
+    #[test]
+    fn gcd_is_found() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(7, 6), 1);
+        assert_eq!(gcd(0, 5), 5);
+    }
+}